@@ -0,0 +1,46 @@
+// Used by the code generated by `error!` and `#[derive(Trasy)]`. Not public
+// API.
+use std::fmt;
+
+use crate::{TrasyError, TrasyErrorKind};
+
+#[cfg(feature = "axum")]
+pub use axum;
+#[cfg(feature = "tonic")]
+pub use tonic;
+
+// Lets `error!` pick `new_with_kind` for inner types that implement
+// `TrasyErrorKind`, and `new` for everything else.
+pub struct Kinded;
+
+pub struct Plain;
+
+pub trait KindedTag {
+    fn trasy_kind_tag(&self) -> Kinded {
+        Kinded
+    }
+}
+
+impl<T: TrasyErrorKind> KindedTag for T {}
+
+pub trait PlainTag {
+    fn trasy_kind_tag(&self) -> Plain {
+        Plain
+    }
+}
+
+impl<T> PlainTag for &T {}
+
+impl Kinded {
+    #[track_caller]
+    pub fn wrap<T: TrasyErrorKind + fmt::Display>(self, inner: T) -> TrasyError<T> {
+        TrasyError::new_with_kind(inner)
+    }
+}
+
+impl Plain {
+    #[track_caller]
+    pub fn wrap<T: fmt::Display>(self, inner: T) -> TrasyError<T> {
+        TrasyError::new(inner)
+    }
+}
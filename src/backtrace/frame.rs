@@ -0,0 +1,377 @@
+use std::backtrace::Backtrace;
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::style::{paint, CYAN, DIM, GREEN};
+
+// Crates whose frames are runtime plumbing rather than application code.
+const HIDDEN_CRATES: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "tokio",
+    "futures",
+    "futures_core",
+    "futures_util",
+    "tracing",
+    "tracing_core",
+    "tracing_error",
+    "trasy",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Frame {
+    pub index: usize,
+    pub function: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl Frame {
+    // `<&dyn core::ops::Fn<..> as ..>::call` belongs to `core`.
+    pub fn crate_name(&self) -> Option<&str> {
+        let name = self
+            .function
+            .trim_start_matches(['<', '&'])
+            .trim_start_matches("dyn ")
+            .trim_start_matches("mut ");
+        let end = name.find("::")?;
+        let crate_name = &name[..end];
+        (!crate_name.is_empty() && !crate_name.contains(['<', ' '])).then_some(crate_name)
+    }
+
+    fn is_runtime(&self) -> bool {
+        match self.crate_name() {
+            Some(crate_name) => HIDDEN_CRATES.contains(&crate_name) || crate_name.starts_with("__"),
+            // Unqualified symbols such as `main`, `_start` or `<unknown>`.
+            None => true,
+        }
+    }
+
+    fn same_site(&self, other: &Frame) -> bool {
+        self.function == other.function && self.file == other.file && self.line == other.line
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>4}: {}", self.index, self.function)?;
+        if let Some(file) = &self.file {
+            write!(f, "\n        at {}", file)?;
+            if let Some(line) = self.line {
+                write!(f, ":{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Parses the `Display` output of a captured `Backtrace`, since std has no
+// stable API for walking its frames.
+pub fn frames(backtrace: &Backtrace) -> Vec<Frame> {
+    parse_frames(&backtrace.to_string())
+}
+
+fn parse_frames(rendered: &str) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = Vec::new();
+
+    for line in rendered.lines() {
+        let line = line.trim();
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                let (file, line, column) = parse_location(location);
+                frame.file = Some(file);
+                frame.line = line;
+                frame.column = column;
+            }
+            continue;
+        }
+
+        let (index, function) = match line.split_once(": ") {
+            Some((index, function)) if index.bytes().all(|b| b.is_ascii_digit()) => {
+                (index.parse().unwrap_or_default(), function)
+            }
+            // Inlined symbols are printed without an index of their own.
+            _ => match frames.last() {
+                Some(previous) if !line.is_empty() => (previous.index, line),
+                _ => continue,
+            },
+        };
+        frames.push(Frame {
+            index,
+            function: function.to_string(),
+            file: None,
+            line: None,
+            column: None,
+        });
+    }
+
+    frames
+}
+
+fn parse_location(location: &str) -> (String, Option<u32>, Option<u32>) {
+    let mut parts = location.rsplitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(column), Some(line), Some(file)) => match (line.parse(), column.parse()) {
+            (Ok(line), Ok(column)) => (file.to_string(), Some(line), Some(column)),
+            _ => (location.to_string(), None, None),
+        },
+        _ => (location.to_string(), None, None),
+    }
+}
+
+// Decides which frames are shown when a backtrace is rendered. By default
+// std, tokio, futures, tracing and trasy's own frames are hidden.
+#[derive(Debug, Clone, Default)]
+pub struct FrameFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    show_all: bool,
+}
+
+impl FrameFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Shows the frames of a crate that is hidden by default.
+    pub fn include<S: Into<String>>(mut self, crate_name: S) -> Self {
+        self.include.push(crate_name.into());
+        self
+    }
+
+    // Hides the frames of a crate. Takes precedence over `include`.
+    pub fn exclude<S: Into<String>>(mut self, crate_name: S) -> Self {
+        self.exclude.push(crate_name.into());
+        self
+    }
+
+    // Disables the default hiding of runtime frames.
+    pub fn show_all(mut self, show_all: bool) -> Self {
+        self.show_all = show_all;
+        self
+    }
+
+    pub fn is_visible(&self, frame: &Frame) -> bool {
+        let crate_name = frame.crate_name();
+        let listed = |crates: &[String]| crate_name.is_some_and(|c| crates.iter().any(|x| x == c));
+
+        if listed(&self.exclude) {
+            return false;
+        }
+        self.show_all || listed(&self.include) || !frame.is_runtime()
+    }
+
+    pub fn render<'a>(&'a self, backtrace: &'a Backtrace) -> RenderedBacktrace<'a> {
+        RenderedBacktrace {
+            backtrace,
+            filter: self,
+            color: false,
+        }
+    }
+}
+
+static FRAME_FILTER: RwLock<Option<FrameFilter>> = RwLock::new(None);
+
+// Sets the filter used when a `TrasyError` is displayed.
+pub fn set_frame_filter(filter: FrameFilter) {
+    *FRAME_FILTER.write().unwrap_or_else(|e| e.into_inner()) = Some(filter);
+}
+
+pub(crate) fn with_frame_filter<R>(f: impl FnOnce(&FrameFilter) -> R) -> R {
+    let filter = FRAME_FILTER.read().unwrap_or_else(|e| e.into_inner());
+    match filter.as_ref() {
+        Some(filter) => f(filter),
+        None => f(&FrameFilter::default()),
+    }
+}
+
+// One frame per line; hidden frames are summarized and repeated frames
+// (e.g. recursion) are collapsed into one.
+pub struct RenderedBacktrace<'a> {
+    backtrace: &'a Backtrace,
+    filter: &'a FrameFilter,
+    color: bool,
+}
+
+impl RenderedBacktrace<'_> {
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn write_frame(&self, f: &mut fmt::Formatter<'_>, frame: &Frame) -> fmt::Result {
+        if !self.color {
+            return write!(f, "{}", frame);
+        }
+
+        write!(
+            f,
+            "{:>4}: {}",
+            frame.index,
+            paint(&frame.function, GREEN, true)
+        )?;
+        if let Some(file) = &frame.file {
+            let location = match frame.line {
+                Some(line) => format!("{}:{}", file, line),
+                None => file.clone(),
+            };
+            write!(f, "\n        at {}", paint(location, DIM, true))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RenderedBacktrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frames = frames(self.backtrace);
+        if frames.is_empty() {
+            return writeln!(f, "{}", self.backtrace);
+        }
+
+        let mut hidden = 0;
+        let mut frames = frames.iter().peekable();
+        while let Some(frame) = frames.next() {
+            if !self.filter.is_visible(frame) {
+                hidden += 1;
+                continue;
+            }
+            write_hidden(f, &mut hidden, self.color)?;
+
+            let mut repeated = 1;
+            while frames.next_if(|next| next.same_site(frame)).is_some() {
+                repeated += 1;
+            }
+            self.write_frame(f, frame)?;
+            if repeated > 1 {
+                write!(f, " (repeated {} times)", repeated)?;
+            }
+            writeln!(f)?;
+        }
+        write_hidden(f, &mut hidden, self.color)
+    }
+}
+
+fn write_hidden(f: &mut fmt::Formatter<'_>, hidden: &mut usize, color: bool) -> fmt::Result {
+    let message = match *hidden {
+        0 => return Ok(()),
+        1 => "... 1 frame hidden ...".to_string(),
+        n => format!("... {} frames hidden ...", n),
+    };
+    writeln!(f, "      {}", paint(message, CYAN, color))?;
+    *hidden = 0;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENDERED: &str = "   0: trasy::TrasyError<T>::new
+             at ./src/lib.rs:68:29
+   1: app::handlers::checkout::{{closure}}
+             at ./src/handlers.rs:42:17
+      app::handlers::checkout
+             at ./src/handlers.rs:40:5
+   2: <alloc::boxed::Box<F,A> as core::ops::function::FnOnce<Args>>::call_once
+             at /rustc/library/alloc/src/boxed.rs:2015:9
+   3: main
+   4: __libc_start_main
+   5: _start
+   6: <unknown>
+";
+
+    fn frame(function: &str) -> Frame {
+        Frame {
+            index: 0,
+            function: function.to_string(),
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    #[test]
+    fn parses_rendered_frames() {
+        let frames = parse_frames(RENDERED);
+        assert_eq!(frames.len(), 8);
+
+        assert_eq!(frames[0].index, 0);
+        assert_eq!(frames[0].function, "trasy::TrasyError<T>::new");
+        assert_eq!(frames[0].file.as_deref(), Some("./src/lib.rs"));
+        assert_eq!((frames[0].line, frames[0].column), (Some(68), Some(29)));
+
+        // An inlined symbol shares the index of the frame before it.
+        assert_eq!(frames[1].function, "app::handlers::checkout::{{closure}}");
+        assert_eq!(frames[2].index, 1);
+        assert_eq!(frames[2].function, "app::handlers::checkout");
+        assert_eq!(frames[2].line, Some(40));
+
+        // Frames without a location keep `None`.
+        assert_eq!(frames[4].function, "main");
+        assert_eq!(frames[4].file, None);
+        assert_eq!(frames[4].line, None);
+        assert_eq!(frames[7].function, "<unknown>");
+    }
+
+    #[test]
+    fn parses_locations() {
+        assert_eq!(
+            parse_location("./src/main.rs:10:5"),
+            ("./src/main.rs".to_string(), Some(10), Some(5))
+        );
+        assert_eq!(
+            parse_location("C:\\app\\src\\main.rs:10:5"),
+            ("C:\\app\\src\\main.rs".to_string(), Some(10), Some(5))
+        );
+        assert_eq!(
+            parse_location("./src/main.rs"),
+            ("./src/main.rs".to_string(), None, None)
+        );
+        assert_eq!(
+            parse_location("./src/main.rs:ten:5"),
+            ("./src/main.rs:ten:5".to_string(), None, None)
+        );
+    }
+
+    #[test]
+    fn crate_names() {
+        let cases = [
+            ("trasy::TrasyError<T>::new", Some("trasy")),
+            ("app::handlers::checkout::{{closure}}", Some("app")),
+            (
+                "<alloc::boxed::Box<F,A> as core::ops::function::FnOnce<Args>>::call_once",
+                Some("alloc"),
+            ),
+            (
+                "<&dyn core::ops::Fn<(), Output = ()> as core::ops::Fn>::call",
+                Some("core"),
+            ),
+            (
+                "<&mut std::io::Stderr as std::io::Write>::write",
+                Some("std"),
+            ),
+            ("main", None),
+            ("_start", None),
+            ("<unknown>", None),
+        ];
+        for (function, expected) in cases {
+            assert_eq!(frame(function).crate_name(), expected, "{}", function);
+        }
+    }
+
+    #[test]
+    fn unqualified_and_runtime_frames_are_hidden() {
+        let filter = FrameFilter::default();
+        assert!(filter.is_visible(&frame("app::handlers::checkout::{{closure}}")));
+        assert!(!filter.is_visible(&frame("trasy::TrasyError<T>::new")));
+        assert!(!filter.is_visible(&frame("__libc_start_main")));
+        assert!(!filter.is_visible(&frame("main")));
+
+        let filter = FrameFilter::new().include("tokio").exclude("app");
+        assert!(filter.is_visible(&frame("tokio::runtime::park::block_on")));
+        assert!(!filter.is_visible(&frame("app::main")));
+    }
+}
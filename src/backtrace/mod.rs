@@ -0,0 +1,152 @@
+use std::any::TypeId;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+mod frame;
+
+pub(crate) use frame::with_frame_filter;
+pub use frame::{frames, set_frame_filter, Frame, FrameFilter, RenderedBacktrace};
+
+// When a `TrasyError` captures a backtrace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BacktracePolicy {
+    Always,
+    Never,
+    // Follows `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`, like `Backtrace::capture()`.
+    #[default]
+    Env,
+    DebugOnly,
+    // Captures one backtrace out of every N errors. `Sampled(0)` never captures.
+    // The global policy and each per-type policy count their errors apart.
+    Sampled(u32),
+}
+
+// A per-type policy with its own count for `Sampled`.
+struct TypePolicy {
+    policy: BacktracePolicy,
+    samples: AtomicU64,
+}
+
+static POLICY: RwLock<BacktracePolicy> = RwLock::new(BacktracePolicy::Env);
+static TYPE_POLICIES: OnceLock<RwLock<HashMap<TypeId, TypePolicy>>> = OnceLock::new();
+static HAS_TYPE_POLICIES: AtomicBool = AtomicBool::new(false);
+static SAMPLES: AtomicU64 = AtomicU64::new(0);
+
+pub fn set_backtrace_policy(policy: BacktracePolicy) {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+pub fn backtrace_policy() -> BacktracePolicy {
+    *POLICY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+// Overrides the global policy for errors whose inner type is `T`. For
+// `trasy::Error` and `ResultExt::context`, `T` is the wrapped error type.
+pub fn set_backtrace_policy_for<T: ?Sized>(policy: BacktracePolicy) {
+    type_policies()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            type_id::<T>(),
+            TypePolicy {
+                policy,
+                samples: AtomicU64::new(0),
+            },
+        );
+    HAS_TYPE_POLICIES.store(true, Ordering::Release);
+}
+
+fn type_policies() -> &'static RwLock<HashMap<TypeId, TypePolicy>> {
+    TYPE_POLICIES.get_or_init(Default::default)
+}
+
+// `TypeId::of` for types that are not `'static`, like the inner type of
+// `TrasyError::new`. Lifetimes are erased, so `&'a str` and `&'static str`
+// share an id. Same trick as the `typeid` crate.
+fn type_id<T: ?Sized>() -> TypeId {
+    trait NonStaticAny {
+        fn get_type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+        fn get_type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<T>()
+        }
+    }
+
+    let phantom = PhantomData::<T>;
+    // SAFETY: `TypeId::of` does not depend on lifetimes, and `phantom` holds
+    // no data that could be reached through the extended lifetime.
+    let phantom = unsafe {
+        std::mem::transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom)
+    };
+    phantom.get_type_id()
+}
+
+pub(crate) fn capture<T: ?Sized>() -> Option<Backtrace> {
+    if HAS_TYPE_POLICIES.load(Ordering::Acquire) {
+        let policies = type_policies().read().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = policies.get(&type_id::<T>()) {
+            return capture_with(entry.policy, &entry.samples);
+        }
+    }
+    capture_global()
+}
+
+// Follows the global policy, ignoring per-type ones.
+pub(crate) fn capture_global() -> Option<Backtrace> {
+    capture_with(backtrace_policy(), &SAMPLES)
+}
+
+fn capture_with(policy: BacktracePolicy, samples: &AtomicU64) -> Option<Backtrace> {
+    match policy {
+        BacktracePolicy::Always => Some(Backtrace::force_capture()),
+        BacktracePolicy::Never => None,
+        BacktracePolicy::Env => {
+            let backtrace = Backtrace::capture();
+            (backtrace.status() == BacktraceStatus::Captured).then_some(backtrace)
+        }
+        BacktracePolicy::DebugOnly if cfg!(debug_assertions) => Some(Backtrace::force_capture()),
+        BacktracePolicy::DebugOnly => None,
+        BacktracePolicy::Sampled(0) => None,
+        BacktracePolicy::Sampled(n) => {
+            let count = samples.fetch_add(1, Ordering::Relaxed);
+            (count % u64::from(n) == 0).then(Backtrace::force_capture)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampled_captures_one_in_n() {
+        let samples = AtomicU64::new(0);
+        let captured = (0..12)
+            .filter(|_| capture_with(BacktracePolicy::Sampled(4), &samples).is_some())
+            .count();
+        assert_eq!(captured, 3);
+        assert!(capture_with(BacktracePolicy::Sampled(0), &samples).is_none());
+    }
+
+    #[test]
+    fn type_ids_ignore_lifetimes() {
+        fn borrowed<'a>(_: &'a str) -> TypeId {
+            type_id::<&'a str>()
+        }
+
+        let text = String::from("borrowed");
+        assert_eq!(borrowed(&text), TypeId::of::<&'static str>());
+        assert_ne!(type_id::<u32>(), type_id::<i32>());
+        assert_eq!(type_id::<str>(), TypeId::of::<str>());
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Once;
+
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+// What to do when a `SpanTrace` cannot be captured because the global
+// subscriber has no `tracing_error::ErrorLayer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpanContextCheck {
+    Off,
+    // Emits a single `tracing::warn!` on the first capture without `ErrorLayer`.
+    #[default]
+    Warn,
+    // Panics in debug builds, warns once in release builds.
+    Assert,
+}
+
+impl SpanContextCheck {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => SpanContextCheck::Off,
+            2 => SpanContextCheck::Assert,
+            _ => SpanContextCheck::Warn,
+        }
+    }
+}
+
+static CHECK: AtomicU8 = AtomicU8::new(SpanContextCheck::Warn as u8);
+static WARNED: Once = Once::new();
+
+pub fn set_span_context_check(check: SpanContextCheck) {
+    CHECK.store(check as u8, Ordering::Relaxed);
+}
+
+pub fn span_context_check() -> SpanContextCheck {
+    SpanContextCheck::from_u8(CHECK.load(Ordering::Relaxed))
+}
+
+const MISSING_ERROR_LAYER: &str = "TrasyError captured an empty span trace because the global \
+     subscriber has no `tracing_error::ErrorLayer`; install one (or use `trasy::init()`) to \
+     get the span context in errors";
+
+pub(crate) fn check_span_trace(context: &SpanTrace) {
+    if context.status() != SpanTraceStatus::UNSUPPORTED {
+        return;
+    }
+
+    match span_context_check() {
+        SpanContextCheck::Off => {}
+        SpanContextCheck::Warn => warn_once(),
+        SpanContextCheck::Assert => {
+            debug_assert!(false, "{}", MISSING_ERROR_LAYER);
+            warn_once();
+        }
+    }
+}
+
+fn warn_once() {
+    WARNED.call_once(|| tracing::warn!("{}", MISSING_ERROR_LAYER));
+}
@@ -0,0 +1,179 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::{kind, TrasyError};
+
+// Like `DynError`, `Error` does not implement `std::error::Error`: it would
+// then be one of the `E`s of its own `From<E>` impl. Use `into_std` at
+// boundaries that need a `std::error::Error`.
+pub type Error = TrasyError<DynError>;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+// Deliberately does not implement `std::error::Error`, otherwise the blanket
+// `From` impl below would overlap with `impl<T> From<T> for T`.
+pub struct DynError(Box<dyn StdError + Send + Sync>);
+
+impl DynError {
+    pub fn new<E: StdError + Send + Sync + 'static>(error: E) -> Self {
+        Self(Box::new(error))
+    }
+
+    pub fn msg(message: String) -> Self {
+        Self(message.into())
+    }
+
+    pub fn into_inner(self) -> Box<dyn StdError + Send + Sync> {
+        self.0
+    }
+}
+
+impl Deref for DynError {
+    type Target = dyn StdError + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl AsRef<dyn StdError + Send + Sync> for DynError {
+    fn as_ref(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+// `DynError` as a `std::error::Error`, for boundaries such as anyhow and eyre
+// that need one. `source()` is forwarded, so the chain stays intact.
+pub struct BoxedError(Box<dyn StdError + Send + Sync>);
+
+impl BoxedError {
+    pub fn into_inner(self) -> Box<dyn StdError + Send + Sync> {
+        self.0
+    }
+}
+
+impl Deref for BoxedError {
+    type Target = dyn StdError + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for BoxedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for BoxedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl StdError for BoxedError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0.source()
+    }
+}
+
+impl<T: StdError + Send + Sync + 'static> TrasyError<T> {
+    // Type-erases the inner error while keeping the context captured at
+    // creation. `?` into `trasy::Error` would wrap the `TrasyError` and capture
+    // everything again, so convert with `.map_err(TrasyError::boxed)?`.
+    pub fn boxed(self) -> Error {
+        let kind = self
+            .kind
+            .or_else(|| kind::registered_kind(&self.inner).map(Box::new));
+        TrasyError {
+            context: self.context,
+            backtrace: self.backtrace,
+            location: self.location,
+            fields: self.fields,
+            kind,
+            inner: DynError::new(self.inner),
+        }
+    }
+}
+
+impl Error {
+    // A `TrasyError` that implements `std::error::Error`, with the context
+    // captured at creation.
+    pub fn into_std(self) -> TrasyError<BoxedError> {
+        TrasyError {
+            context: self.context,
+            backtrace: self.backtrace,
+            location: self.location,
+            fields: self.fields,
+            kind: self.kind,
+            inner: BoxedError(self.inner.0),
+        }
+    }
+
+    pub fn is<E: StdError + 'static>(&self) -> bool {
+        self.inner.0.is::<E>()
+    }
+
+    pub fn downcast_ref<E: StdError + 'static>(&self) -> Option<&E> {
+        self.inner.0.downcast_ref::<E>()
+    }
+
+    pub fn downcast_mut<E: StdError + 'static>(&mut self) -> Option<&mut E> {
+        self.inner.0.downcast_mut::<E>()
+    }
+
+    pub fn downcast<E: StdError + 'static>(self) -> Result<TrasyError<E>, Self> {
+        let TrasyError {
+            context,
+            backtrace,
+            location,
+            fields,
+            kind,
+            inner,
+        } = self;
+
+        match inner.0.downcast::<E>() {
+            Ok(inner) => Ok(TrasyError {
+                context,
+                backtrace,
+                location,
+                fields,
+                kind,
+                inner: *inner,
+            }),
+            Err(inner) => Err(TrasyError {
+                context,
+                backtrace,
+                location,
+                fields,
+                kind,
+                inner: DynError(inner),
+            }),
+        }
+    }
+}
+
+impl<E: StdError + Send + Sync + 'static> From<E> for Error {
+    #[track_caller]
+    fn from(error: E) -> Self {
+        let kind = kind::registered_kind(&error);
+        let mut error = TrasyError::capture(DynError::new(error), crate::backtrace::capture::<E>());
+        error.kind = kind.map(Box::new);
+        crate::otel::record_on_creation(&error);
+        error
+    }
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{backtrace, TrasyError};
+
+#[derive(Debug)]
+pub struct ContextError<C, E> {
+    context: C,
+    error: E,
+}
+
+impl<C, E> ContextError<C, E> {
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<C: fmt::Display, E: fmt::Display> fmt::Display for ContextError<C, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `{:#}` also prints the wrapped error, the same way anyhow does it.
+        if f.alternate() {
+            write!(f, "{}: {}", self.context, self.error)
+        } else {
+            write!(f, "{}", self.context)
+        }
+    }
+}
+
+impl<C, E> Error for ContextError<C, E>
+where
+    C: fmt::Debug + fmt::Display,
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+pub trait ResultExt<T, E> {
+    fn context<C>(self, context: C) -> Result<T, TrasyError<ContextError<C, E>>>
+    where
+        C: fmt::Display;
+
+    fn with_context<C, F>(self, f: F) -> Result<T, TrasyError<ContextError<C, E>>>
+    where
+        C: fmt::Display,
+        F: FnOnce() -> C;
+}
+
+// `#[track_caller]` does not reach into closures, hence the `match`es below
+// instead of `map_err`/`ok_or_else`.
+impl<T, E: fmt::Display> ResultExt<T, E> for Result<T, E> {
+    #[track_caller]
+    fn context<C>(self, context: C) -> Result<T, TrasyError<ContextError<C, E>>>
+    where
+        C: fmt::Display,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(TrasyError::from_parts(
+                ContextError { context, error },
+                backtrace::capture::<E>(),
+            )),
+        }
+    }
+
+    #[track_caller]
+    fn with_context<C, F>(self, f: F) -> Result<T, TrasyError<ContextError<C, E>>>
+    where
+        C: fmt::Display,
+        F: FnOnce() -> C,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(TrasyError::from_parts(
+                ContextError {
+                    context: f(),
+                    error,
+                },
+                backtrace::capture::<E>(),
+            )),
+        }
+    }
+}
+
+pub trait OptionExt<T> {
+    fn context<C>(self, context: C) -> Result<T, TrasyError<C>>
+    where
+        C: fmt::Display;
+
+    fn with_context<C, F>(self, f: F) -> Result<T, TrasyError<C>>
+    where
+        C: fmt::Display,
+        F: FnOnce() -> C;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    #[track_caller]
+    fn context<C>(self, context: C) -> Result<T, TrasyError<C>>
+    where
+        C: fmt::Display,
+    {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(TrasyError::new(context)),
+        }
+    }
+
+    #[track_caller]
+    fn with_context<C, F>(self, f: F) -> Result<T, TrasyError<C>>
+    where
+        C: fmt::Display,
+        F: FnOnce() -> C,
+    {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(TrasyError::new(f())),
+        }
+    }
+}
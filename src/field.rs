@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+
+use opentelemetry::Value;
+
+// Conversion used by `TrasyError::with_field` and the `error!(e, key = value)`
+// form. Integer literals fall back to `i32`, so every primitive gets its own
+// impl instead of going through `Into<Value>`.
+pub trait FieldValue {
+    fn into_value(self) -> Value;
+}
+
+macro_rules! int_field_values {
+    ($($t:ty),+) => {
+        $(
+            impl FieldValue for $t {
+                fn into_value(self) -> Value {
+                    Value::I64(self as i64)
+                }
+            }
+        )+
+    };
+}
+
+int_field_values!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! wide_int_field_values {
+    ($($t:ty),+) => {
+        $(
+            impl FieldValue for $t {
+                fn into_value(self) -> Value {
+                    match i64::try_from(self) {
+                        Ok(value) => Value::I64(value),
+                        Err(_) => Value::String(self.to_string().into()),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+wide_int_field_values!(u64, usize, isize, i128, u128);
+
+impl FieldValue for f32 {
+    fn into_value(self) -> Value {
+        Value::F64(self as f64)
+    }
+}
+
+impl FieldValue for f64 {
+    fn into_value(self) -> Value {
+        Value::F64(self)
+    }
+}
+
+impl FieldValue for bool {
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+}
+
+impl FieldValue for &'static str {
+    fn into_value(self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl FieldValue for String {
+    fn into_value(self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl FieldValue for Cow<'static, str> {
+    fn into_value(self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl FieldValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
@@ -0,0 +1,127 @@
+use std::env;
+use std::io;
+
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Registry};
+
+use crate::telemetry::{setup_opentelemetry, TelemetryConfig, TelemetryGuard};
+use crate::TrasyError;
+
+const DEFAULT_DIRECTIVE: &str = "info";
+
+pub struct Trasy;
+
+impl Trasy {
+    pub fn builder() -> TrasyBuilder {
+        TrasyBuilder::default()
+    }
+}
+
+// Installs `Registry` + `OpenTelemetryLayer` + `ErrorLayer` + `EnvFilter` +
+// fmt layer as the global default subscriber. Every layer can be switched off.
+pub struct TrasyBuilder {
+    opentelemetry: bool,
+    telemetry: Option<TelemetryConfig>,
+    error_layer: bool,
+    env_filter: bool,
+    default_directive: String,
+    fmt: bool,
+}
+
+impl Default for TrasyBuilder {
+    fn default() -> Self {
+        Self {
+            opentelemetry: true,
+            telemetry: None,
+            error_layer: true,
+            env_filter: true,
+            default_directive: DEFAULT_DIRECTIVE.to_string(),
+            fmt: true,
+        }
+    }
+}
+
+impl TrasyBuilder {
+    pub fn opentelemetry(mut self, enabled: bool) -> Self {
+        self.opentelemetry = enabled;
+        self
+    }
+
+    // Without an explicit config, `TelemetryConfig::from_env()` is used.
+    pub fn telemetry(mut self, config: TelemetryConfig) -> Self {
+        self.telemetry = Some(config);
+        self
+    }
+
+    // Without the `ErrorLayer`, the span trace captured by `TrasyError` is empty.
+    pub fn error_layer(mut self, enabled: bool) -> Self {
+        self.error_layer = enabled;
+        self
+    }
+
+    pub fn env_filter(mut self, enabled: bool) -> Self {
+        self.env_filter = enabled;
+        self
+    }
+
+    // Used by the `EnvFilter` when `RUST_LOG` is not set.
+    pub fn default_directive<S: Into<String>>(mut self, directive: S) -> Self {
+        self.default_directive = directive.into();
+        self
+    }
+
+    pub fn fmt(mut self, enabled: bool) -> Self {
+        self.fmt = enabled;
+        self
+    }
+
+    // Async because the batch span processor and the HTTP/JSON exporter are
+    // spawned on the tokio runtime this is awaited on, so a runtime is needed
+    // either way.
+    pub async fn install(self) -> Result<TelemetryGuard, TrasyError<io::Error>> {
+        let env_filter = if self.env_filter {
+            Some(env_filter(&self.default_directive)?)
+        } else {
+            None
+        };
+
+        let (opentelemetry, guard) = if self.opentelemetry {
+            let config = match self.telemetry {
+                Some(config) => config,
+                None => TelemetryConfig::from_env()?,
+            };
+            let (layer, guard) = setup_opentelemetry(config).await?;
+            (Some(layer), guard)
+        } else {
+            (None, TelemetryGuard::new(None, Default::default()))
+        };
+
+        let subscriber = Registry::default()
+            .with(opentelemetry)
+            .with(self.error_layer.then(ErrorLayer::default))
+            .with(env_filter)
+            .with(self.fmt.then(tracing_subscriber::fmt::layer));
+
+        tracing::subscriber::set_global_default(subscriber)
+            .map_err(|e| TrasyError::new(io::Error::other(e)))?;
+
+        Ok(guard)
+    }
+}
+
+fn env_filter(default_directive: &str) -> Result<EnvFilter, TrasyError<io::Error>> {
+    let directives = env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_| default_directive.into());
+    EnvFilter::try_new(&directives).map_err(|e| {
+        TrasyError::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid filter directives `{}`: {}", directives, e),
+        ))
+    })
+}
+
+// Installs the complete subscriber with every layer enabled and the telemetry
+// configured from the standard `OTEL_*` environment variables.
+pub async fn init() -> Result<TelemetryGuard, TrasyError<io::Error>> {
+    Trasy::builder().install().await
+}
@@ -0,0 +1,70 @@
+use std::backtrace::Backtrace;
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::{Error, TrasyError};
+
+// Inner error of a `TrasyError` converted from `anyhow::Error`. The context
+// chain of the anyhow error becomes the `source()` chain, and the backtrace
+// anyhow captured stays available through `backtrace()`.
+//
+// `trasy::Error` cannot implement `From<anyhow::Error>` itself, because it
+// would overlap with its blanket impl if anyhow ever implemented
+// `std::error::Error`. Use `boxed()` to get a `trasy::Error`.
+pub struct AnyhowError(anyhow::Error);
+
+impl AnyhowError {
+    pub fn backtrace(&self) -> &Backtrace {
+        self.0.backtrace()
+    }
+
+    pub fn into_inner(self) -> anyhow::Error {
+        self.0
+    }
+}
+
+impl Deref for AnyhowError {
+    type Target = anyhow::Error;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for AnyhowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for AnyhowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl StdError for AnyhowError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0.source()
+    }
+}
+
+impl From<anyhow::Error> for TrasyError<AnyhowError> {
+    #[track_caller]
+    fn from(error: anyhow::Error) -> Self {
+        TrasyError::from_parts(
+            AnyhowError(error),
+            crate::backtrace::capture::<anyhow::Error>(),
+        )
+    }
+}
+
+// `TrasyError<T>` already converts through anyhow's blanket impl. Both keep
+// the whole error and its `source()` chain; the span trace is reachable with
+// `downcast_ref::<TrasyError<T>>()`, or `TrasyError<BoxedError>` for this one.
+impl From<Error> for anyhow::Error {
+    fn from(error: Error) -> Self {
+        anyhow::Error::new(error.into_std())
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::{otel, ContextError, DynError, TrasyError};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+
+// Maps an inner error type to the HTTP response of a `TrasyError`.
+pub trait HttpStatus {
+    fn status_code(&self) -> StatusCode;
+
+    // Sent to the client as the `detail` of the problem document. Defaults to
+    // nothing, so internal error messages never leave the server.
+    fn public_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl HttpStatus for DynError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+// `.context(..)` keeps the response of the wrapped error; the context itself
+// is only logged.
+impl<C, E: HttpStatus> HttpStatus for ContextError<C, E> {
+    fn status_code(&self) -> StatusCode {
+        self.error().status_code()
+    }
+
+    fn public_message(&self) -> Option<String> {
+        self.error().public_message()
+    }
+}
+
+// Responds with an RFC 9457 problem document holding the status, the public
+// message and the trace ID of the current span. The full error, with span
+// trace and backtrace, is only logged.
+impl<T: HttpStatus + fmt::Debug + fmt::Display> IntoResponse for TrasyError<T> {
+    fn into_response(self) -> Response {
+        let status = self.inner.status_code();
+        self.record_at_boundary();
+        if status.is_server_error() {
+            tracing::error!(status = status.as_u16(), "{}", self);
+        } else {
+            tracing::warn!(status = status.as_u16(), "{}", self);
+        }
+
+        let mut problem = serde_json::json!({
+            "type": "about:blank",
+            "title": status.canonical_reason().unwrap_or("Unknown Status"),
+            "status": status.as_u16(),
+        });
+        if let Some(detail) = self.inner.public_message() {
+            problem["detail"] = detail.into();
+        }
+        if let Some(span_context) = otel::current_span_context() {
+            problem["trace_id"] = span_context.trace_id().to_string().into();
+        }
+
+        let mut response = (status, problem.to_string()).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+        response
+    }
+}
@@ -0,0 +1,60 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::{Error, TrasyError};
+
+// Inner error of a `TrasyError` converted from `eyre::Report`. The chain of
+// the report becomes the `source()` chain.
+pub struct EyreError(eyre::Report);
+
+impl EyreError {
+    pub fn into_inner(self) -> eyre::Report {
+        self.0
+    }
+}
+
+impl Deref for EyreError {
+    type Target = eyre::Report;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for EyreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for EyreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl StdError for EyreError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.0.source()
+    }
+}
+
+impl From<eyre::Report> for TrasyError<EyreError> {
+    #[track_caller]
+    fn from(error: eyre::Report) -> Self {
+        TrasyError::from_parts(
+            EyreError(error),
+            crate::backtrace::capture::<eyre::Report>(),
+        )
+    }
+}
+
+// `TrasyError<T>` already converts through eyre's blanket impl. Both keep the
+// whole error and its `source()` chain; the span trace is reachable with
+// `downcast_ref::<TrasyError<T>>()`, or `TrasyError<BoxedError>` for this one.
+impl From<Error> for eyre::Report {
+    fn from(error: Error) -> Self {
+        eyre::Report::new(error.into_std())
+    }
+}
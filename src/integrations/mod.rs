@@ -0,0 +1,17 @@
+#[cfg(feature = "anyhow")]
+mod anyhow;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "eyre")]
+mod eyre;
+#[cfg(feature = "tonic")]
+mod tonic;
+
+#[cfg(feature = "anyhow")]
+pub use self::anyhow::AnyhowError;
+#[cfg(feature = "axum")]
+pub use self::axum::HttpStatus;
+#[cfg(feature = "eyre")]
+pub use self::eyre::EyreError;
+#[cfg(feature = "tonic")]
+pub use self::tonic::{set_grpc_status_options, GrpcStatus, GrpcStatusOptions};
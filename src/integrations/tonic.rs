@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+
+use prost::Message;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::Code;
+
+use crate::backtrace::with_frame_filter;
+use crate::{otel, ContextError, DynError, Frame, TrasyError};
+
+const ERROR_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+const DEBUG_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.DebugInfo";
+const DEFAULT_DOMAIN: &str = "trasy";
+
+// Maps an inner error type to the gRPC status of a `TrasyError`.
+pub trait GrpcStatus {
+    fn grpc_code(&self) -> Code;
+
+    // `reason` of the `google.rpc.ErrorInfo` detail, an UPPER_SNAKE_CASE
+    // identifier. Defaults to the name of the status code.
+    fn reason(&self) -> Option<String> {
+        None
+    }
+
+    // Sent to the client as the status message. Defaults to the description
+    // of the code, so internal error messages never leave the server.
+    fn public_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl GrpcStatus for DynError {
+    fn grpc_code(&self) -> Code {
+        Code::Internal
+    }
+}
+
+// `.context(..)` keeps the status of the wrapped error; the context itself is
+// only logged.
+impl<C, E: GrpcStatus> GrpcStatus for ContextError<C, E> {
+    fn grpc_code(&self) -> Code {
+        self.error().grpc_code()
+    }
+
+    fn reason(&self) -> Option<String> {
+        self.error().reason()
+    }
+
+    fn public_message(&self) -> Option<String> {
+        self.error().public_message()
+    }
+}
+
+// How a `TrasyError` is converted into a `tonic::Status`.
+#[derive(Debug, Clone)]
+pub struct GrpcStatusOptions {
+    domain: String,
+    debug_info: bool,
+}
+
+impl Default for GrpcStatusOptions {
+    fn default() -> Self {
+        Self {
+            domain: DEFAULT_DOMAIN.to_string(),
+            debug_info: false,
+        }
+    }
+}
+
+impl GrpcStatusOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `domain` of the `ErrorInfo` detail, usually the DNS name of the service,
+    // e.g. `users.example.com`. Defaults to `trasy`.
+    pub fn domain<S: Into<String>>(mut self, domain: S) -> Self {
+        self.domain = domain.into();
+        self
+    }
+
+    // Adds a `DebugInfo` detail with the full error message and the visible
+    // backtrace frames. Off by default, since it exposes internals to clients.
+    pub fn debug_info(mut self, debug_info: bool) -> Self {
+        self.debug_info = debug_info;
+        self
+    }
+}
+
+static GRPC_STATUS_OPTIONS: RwLock<Option<GrpcStatusOptions>> = RwLock::new(None);
+
+pub fn set_grpc_status_options(options: GrpcStatusOptions) {
+    *GRPC_STATUS_OPTIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = Some(options);
+}
+
+fn grpc_status_options() -> GrpcStatusOptions {
+    GRPC_STATUS_OPTIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+// google.rpc.Status, ErrorInfo and DebugInfo from
+// https://github.com/googleapis/googleapis/tree/master/google/rpc
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ErrorInfo {
+    #[prost(string, tag = "1")]
+    reason: String,
+    #[prost(string, tag = "2")]
+    domain: String,
+    #[prost(map = "string, string", tag = "3")]
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DebugInfo {
+    #[prost(string, repeated, tag = "1")]
+    stack_entries: Vec<String>,
+    #[prost(string, tag = "2")]
+    detail: String,
+}
+
+// The status carries an `ErrorInfo` with the trace ID and the span names of
+// the span trace and, when enabled in `GrpcStatusOptions`, a `DebugInfo` with
+// the message and the backtrace frames. The W3C `traceparent` of the current
+// span is set in the response metadata.
+impl<T: GrpcStatus + fmt::Display> From<TrasyError<T>> for tonic::Status {
+    fn from(error: TrasyError<T>) -> Self {
+        error.record_at_boundary();
+        let options = grpc_status_options();
+        let code = error.inner.grpc_code();
+        let message = error
+            .inner
+            .public_message()
+            .unwrap_or_else(|| code.description().to_string());
+        let span_context = otel::current_span_context();
+
+        let mut metadata = HashMap::new();
+        let spans: Vec<&str> = error.spans().map(|span| span.name()).collect();
+        if !spans.is_empty() {
+            metadata.insert("spans".to_string(), spans.join(","));
+        }
+        if let Some(span_context) = &span_context {
+            metadata.insert("trace_id".to_string(), span_context.trace_id().to_string());
+        }
+        let error_info = ErrorInfo {
+            reason: error
+                .inner
+                .reason()
+                .unwrap_or_else(|| code_name(code).to_string()),
+            domain: options.domain,
+            metadata,
+        };
+        let mut details = vec![prost_types::Any {
+            type_url: ERROR_INFO_TYPE_URL.to_string(),
+            value: error_info.encode_to_vec(),
+        }];
+
+        if options.debug_info {
+            let stack_entries = with_frame_filter(|filter| {
+                error
+                    .frames()
+                    .iter()
+                    .filter(|frame| filter.is_visible(frame))
+                    .map(stack_entry)
+                    .collect()
+            });
+            let debug_info = DebugInfo {
+                stack_entries,
+                detail: error.inner.to_string(),
+            };
+            details.push(prost_types::Any {
+                type_url: DEBUG_INFO_TYPE_URL.to_string(),
+                value: debug_info.encode_to_vec(),
+            });
+        }
+
+        let status = RpcStatus {
+            code: code as i32,
+            message: message.clone(),
+            details,
+        };
+
+        let mut response_metadata = MetadataMap::new();
+        if let Some(span_context) = span_context {
+            let traceparent = format!(
+                "00-{}-{}-{:02x}",
+                span_context.trace_id(),
+                span_context.span_id(),
+                span_context.trace_flags().to_u8()
+            );
+            if let Ok(value) = MetadataValue::try_from(traceparent) {
+                response_metadata.insert("traceparent", value);
+            }
+        }
+
+        tonic::Status::with_details_and_metadata(
+            code,
+            message,
+            status.encode_to_vec().into(),
+            response_metadata,
+        )
+    }
+}
+
+fn stack_entry(frame: &Frame) -> String {
+    match (&frame.file, frame.line) {
+        (Some(file), Some(line)) => format!("{} at {}:{}", frame.function, file, line),
+        (Some(file), None) => format!("{} at {}", frame.function, file),
+        _ => frame.function.clone(),
+    }
+}
+
+fn code_name(code: Code) -> &'static str {
+    match code {
+        Code::Ok => "OK",
+        Code::Cancelled => "CANCELLED",
+        Code::Unknown => "UNKNOWN",
+        Code::InvalidArgument => "INVALID_ARGUMENT",
+        Code::DeadlineExceeded => "DEADLINE_EXCEEDED",
+        Code::NotFound => "NOT_FOUND",
+        Code::AlreadyExists => "ALREADY_EXISTS",
+        Code::PermissionDenied => "PERMISSION_DENIED",
+        Code::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        Code::FailedPrecondition => "FAILED_PRECONDITION",
+        Code::Aborted => "ABORTED",
+        Code::OutOfRange => "OUT_OF_RANGE",
+        Code::Unimplemented => "UNIMPLEMENTED",
+        Code::Internal => "INTERNAL",
+        Code::Unavailable => "UNAVAILABLE",
+        Code::DataLoss => "DATA_LOSS",
+        Code::Unauthenticated => "UNAUTHENTICATED",
+    }
+}
@@ -0,0 +1,144 @@
+use std::any::type_name;
+use std::error::Error as StdError;
+use std::fmt;
+
+use opentelemetry::{Array, Value};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+use crate::backtrace::with_frame_filter;
+use crate::{DynError, Error, Frame, SpanRecord, TrasyError};
+
+// Serialized following `schema/trasy-error.schema.json`. Keep both in sync.
+impl<T: StdError + 'static> Serialize for TrasyError<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(self, type_name::<T>(), self.inner.source(), serializer)
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(
+            self,
+            type_name::<DynError>(),
+            self.inner.source(),
+            serializer,
+        )
+    }
+}
+
+fn serialize_error<T: fmt::Display, S: Serializer>(
+    error: &TrasyError<T>,
+    type_name: &'static str,
+    source: Option<&(dyn StdError + 'static)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let causes: Vec<String> = std::iter::successors(source, |&cause| cause.source())
+        .map(ToString::to_string)
+        .collect();
+    let backtrace: Vec<Frame> = with_frame_filter(|filter| {
+        error
+            .frames()
+            .into_iter()
+            .filter(|frame| filter.is_visible(frame))
+            .collect()
+    });
+
+    let location = Location {
+        file: error.location.file(),
+        line: error.location.line(),
+        column: error.location.column(),
+    };
+
+    let mut state = serializer.serialize_struct("TrasyError", 8)?;
+    state.serialize_field("message", &error.inner.to_string())?;
+    state.serialize_field("type", type_name)?;
+    state.serialize_field("kind", &error.kind())?;
+    state.serialize_field("location", &location)?;
+    state.serialize_field("causes", &causes)?;
+    state.serialize_field("span_trace", &SpanTrace(error))?;
+    state.serialize_field("backtrace", &backtrace)?;
+    state.serialize_field("fields", &Fields(error.fields()))?;
+    state.end()
+}
+
+#[derive(Serialize)]
+struct Location {
+    file: &'static str,
+    line: u32,
+    column: u32,
+}
+
+struct SpanTrace<'a, T>(&'a TrasyError<T>);
+
+impl<T> Serialize for SpanTrace<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for span in self.0.spans() {
+            seq.serialize_element(&Span {
+                name: span.name(),
+                target: span.target(),
+                fields: SpanFields(&span),
+                file: span.file(),
+                line: span.line(),
+            })?;
+        }
+        seq.end()
+    }
+}
+
+#[derive(Serialize)]
+struct Span<'a> {
+    name: &'static str,
+    target: &'static str,
+    fields: SpanFields<'a>,
+    file: Option<&'static str>,
+    line: Option<u32>,
+}
+
+struct SpanFields<'a>(&'a SpanRecord);
+
+impl Serialize for SpanFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (key, value) in self.0.fields() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+struct Fields<'a>(&'a [opentelemetry::KeyValue]);
+
+impl Serialize for Fields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for field in self.0 {
+            map.serialize_entry(field.key.as_str(), &FieldValue(&field.value))?;
+        }
+        map.end()
+    }
+}
+
+struct FieldValue<'a>(&'a Value);
+
+impl Serialize for FieldValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::I64(value) => serializer.serialize_i64(*value),
+            Value::F64(value) => serializer.serialize_f64(*value),
+            Value::String(value) => serializer.serialize_str(value.as_str()),
+            Value::Array(Array::Bool(values)) => values.serialize(serializer),
+            Value::Array(Array::I64(values)) => values.serialize(serializer),
+            Value::Array(Array::F64(values)) => values.serialize(serializer),
+            Value::Array(Array::String(values)) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value.as_str())?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::{ContextError, TrasyError};
+
+// Stable, machine-readable identity of an error, e.g. `E1042` or
+// `db.timeout`, for catalogues and dashboards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ErrorKind {
+    pub code: &'static str,
+    pub category: &'static str,
+    pub doc_url: Option<&'static str>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub exit_code: Option<u8>,
+}
+
+impl ErrorKind {
+    pub const fn new(code: &'static str, category: &'static str) -> Self {
+        Self {
+            code,
+            category,
+            doc_url: None,
+            exit_code: None,
+        }
+    }
+
+    pub const fn with_doc_url(mut self, doc_url: &'static str) -> Self {
+        self.doc_url = Some(doc_url);
+        self
+    }
+
+    pub const fn with_exit_code(mut self, exit_code: u8) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+
+    pub fn of<K: TrasyErrorKind + ?Sized>(error: &K) -> Self {
+        Self {
+            code: error.code(),
+            category: error.category(),
+            doc_url: error.doc_url(),
+            exit_code: error.exit_code(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    Info,
+    Warning,
+    #[default]
+    Error,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+// Implemented by inner error types that carry an `ErrorKind`. Errors created
+// with `error!`, `bail!` or `TrasyError::new_with_kind` record it, and so does
+// `?` into `trasy::Error` once the type is registered with
+// `register_error_kinds`. `TrasyError::new` and `.context(..)` cannot see the
+// trait; call `with_kind` on their errors.
+pub trait TrasyErrorKind {
+    fn code(&self) -> &'static str;
+
+    fn category(&self) -> &'static str;
+
+    fn doc_url(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    // Whether retrying the failed operation may succeed.
+    fn is_retryable(&self) -> bool {
+        false
+    }
+
+    // Process exit code of a `Report` holding this error, unless the exit code
+    // mapper or `Report::with_exit_code` decide otherwise.
+    fn exit_code(&self) -> Option<u8> {
+        None
+    }
+
+    // Every kind the type can produce, listed by `error_kinds` once the type
+    // is registered with `register_error_kinds`.
+    fn kinds() -> Vec<ErrorKind>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+}
+
+// The kind of the wrapped error; the context has none of its own.
+impl<C, E: TrasyErrorKind> TrasyErrorKind for ContextError<C, E> {
+    fn code(&self) -> &'static str {
+        self.error().code()
+    }
+
+    fn category(&self) -> &'static str {
+        self.error().category()
+    }
+
+    fn doc_url(&self) -> Option<&'static str> {
+        self.error().doc_url()
+    }
+
+    fn severity(&self) -> Severity {
+        self.error().severity()
+    }
+
+    fn is_retryable(&self) -> bool {
+        self.error().is_retryable()
+    }
+
+    fn exit_code(&self) -> Option<u8> {
+        self.error().exit_code()
+    }
+
+    fn kinds() -> Vec<ErrorKind> {
+        E::kinds()
+    }
+}
+
+type KindResolver = fn(&dyn Any) -> Option<ErrorKind>;
+
+static KINDS: RwLock<Vec<ErrorKind>> = RwLock::new(Vec::new());
+static RESOLVERS: RwLock<Vec<(TypeId, KindResolver)>> = RwLock::new(Vec::new());
+
+// Lists the kinds of `K` in `error_kinds`, and lets `?` into `trasy::Error`
+// record the kind of a `K`.
+pub fn register_error_kinds<K: TrasyErrorKind + 'static>() {
+    let mut kinds = KINDS.write().unwrap_or_else(|e| e.into_inner());
+    for kind in K::kinds() {
+        if !kinds.iter().any(|known| known.code == kind.code) {
+            kinds.push(kind);
+        }
+    }
+
+    let mut resolvers = RESOLVERS.write().unwrap_or_else(|e| e.into_inner());
+    if !resolvers
+        .iter()
+        .any(|(type_id, _)| *type_id == TypeId::of::<K>())
+    {
+        resolvers.push((TypeId::of::<K>(), resolve::<K>));
+    }
+}
+
+fn resolve<K: TrasyErrorKind + 'static>(error: &dyn Any) -> Option<ErrorKind> {
+    error.downcast_ref::<K>().map(ErrorKind::of)
+}
+
+// The kind of `error` if its type was registered with `register_error_kinds`.
+pub(crate) fn registered_kind<E: 'static>(error: &E) -> Option<ErrorKind> {
+    let resolvers = RESOLVERS.read().unwrap_or_else(|e| e.into_inner());
+    resolvers
+        .iter()
+        .find(|(type_id, _)| *type_id == TypeId::of::<E>())
+        .and_then(|(_, resolve)| resolve(error))
+}
+
+// Registered kinds sorted by code, e.g. to generate an error catalogue.
+pub fn error_kinds() -> Vec<ErrorKind> {
+    let mut kinds = KINDS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    kinds.sort_by_key(|kind| kind.code);
+    kinds
+}
+
+impl<T: TrasyErrorKind + fmt::Display> TrasyError<T> {
+    // Like `new`, and also keeps the kind of `inner` on the error. Its code is
+    // exported as the `error.type` span attribute.
+    #[track_caller]
+    pub fn new_with_kind(inner: T) -> Self {
+        let error = Self::capture(inner, crate::backtrace::capture::<T>()).with_kind();
+        crate::otel::record_on_creation(&error);
+        error
+    }
+}
+
+impl<T: TrasyErrorKind> TrasyError<T> {
+    // Records the kind of the inner error on an error created with `new` or
+    // `.context(..)`, e.g. `.context("..").map_err(TrasyError::with_kind)`.
+    pub fn with_kind(mut self) -> Self {
+        self.kind = Some(Box::new(ErrorKind::of(&self.inner)));
+        self
+    }
+}
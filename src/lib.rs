@@ -1,132 +1,252 @@
 use opentelemetry::KeyValue;
-use opentelemetry_otlp::SpanExporterBuilder;
-use opentelemetry_otlp::WithExportConfig;
-use opentelemetry_sdk::trace;
-use opentelemetry_sdk::trace::Tracer;
-use opentelemetry_sdk::Resource;
 use std::backtrace::Backtrace;
-use std::error::Error;
+use std::error::Error as StdError;
 use std::fmt;
-use tracing_error::SpanTrace;
-use tracing_opentelemetry::OpenTelemetryLayer;
-use tracing_subscriber::Registry;
+use std::panic::Location;
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+#[doc(hidden)]
+pub mod __private;
+mod backtrace;
+mod diagnostics;
+mod dynamic;
+mod ext;
+mod field;
+mod init;
+mod integrations;
+#[cfg(feature = "serde")]
+mod json;
+mod kind;
+mod otel;
+mod panic;
+mod report;
+mod span;
+mod style;
+mod telemetry;
+mod termination;
+
+pub use backtrace::{
+    backtrace_policy, set_backtrace_policy, set_backtrace_policy_for, set_frame_filter,
+    BacktracePolicy, Frame, FrameFilter, RenderedBacktrace,
+};
+pub use diagnostics::{set_span_context_check, span_context_check, SpanContextCheck};
+pub use dynamic::{BoxedError, DynError, Error, Result};
+pub use ext::{ContextError, OptionExt, ResultExt};
+pub use field::FieldValue;
+pub use init::{init, Trasy, TrasyBuilder};
+#[cfg(feature = "anyhow")]
+pub use integrations::AnyhowError;
+#[cfg(feature = "eyre")]
+pub use integrations::EyreError;
+#[cfg(feature = "axum")]
+pub use integrations::HttpStatus;
+#[cfg(feature = "tonic")]
+pub use integrations::{set_grpc_status_options, GrpcStatus, GrpcStatusOptions};
+pub use kind::{error_kinds, register_error_kinds, ErrorKind, Severity, TrasyErrorKind};
+pub use otel::set_record_on_creation;
+pub use panic::install_panic_hook;
+pub use report::ErrorReport;
+pub use span::SpanRecord;
+pub use telemetry::{
+    setup_opentelemetry, Protocol, TelemetryConfig, TelemetryConfigBuilder, TelemetryGuard,
+};
+pub use termination::{set_exit_code_mapper, Report};
+#[cfg(feature = "derive")]
+pub use trasy_derive::Trasy;
 
 #[derive(Debug)]
 pub struct TrasyError<T> {
     context: SpanTrace,
-    backtrace: Option<Backtrace>,
+    backtrace: Option<Box<Backtrace>>,
+    location: &'static Location<'static>,
+    fields: Vec<KeyValue>,
+    kind: Option<Box<ErrorKind>>,
     inner: T,
 }
 
-impl<T> TrasyError<T> {
+impl<T: fmt::Display> TrasyError<T> {
+    // Captures a backtrace according to the `BacktracePolicy` for `T`, and the
+    // caller's location even when no backtrace is captured. The error is
+    // recorded on the current OpenTelemetry span, see `set_record_on_creation`.
+    #[track_caller]
     pub fn new(inner: T) -> Self {
+        Self::from_parts(inner, backtrace::capture::<T>())
+    }
+
+    #[track_caller]
+    fn from_parts(inner: T, backtrace: Option<Backtrace>) -> Self {
+        let error = Self::capture(inner, backtrace);
+        otel::record_on_creation(&error);
+        error
+    }
+
+    // Records the error on the current OpenTelemetry span. Errors are recorded
+    // when they are created unless `set_record_on_creation(false)` was called;
+    // the axum, tonic and `Report` integrations then record them instead.
+    pub fn record(&self) {
+        otel::record_exception(self);
+    }
+
+    // Records errors that were not recorded when they were created.
+    pub(crate) fn record_at_boundary(&self) {
+        if !otel::records_on_creation() {
+            self.record();
+        }
+    }
+}
+
+impl<T> TrasyError<T> {
+    #[track_caller]
+    fn capture(inner: T, backtrace: Option<Backtrace>) -> Self {
+        let context = SpanTrace::capture();
+        diagnostics::check_span_trace(&context);
         Self {
-            context: SpanTrace::capture(),
-            backtrace: None,
+            context,
+            backtrace: backtrace.map(Box::new),
+            location: Location::caller(),
+            fields: Vec::new(),
+            kind: None,
             inner,
         }
     }
 
     pub fn with_backtrace(mut self, backtrace: Backtrace) -> Self {
-        self.backtrace = Some(backtrace);
+        self.backtrace = Some(Box::new(backtrace));
         self
     }
+
+    // Fields are kept on the error and, like the error itself, recorded on the
+    // current OpenTelemetry span as attributes, see `set_record_on_creation`.
+    // `record` adds them to the exception event as well.
+    pub fn with_field<V: FieldValue>(mut self, key: &'static str, value: V) -> Self {
+        let field = KeyValue::new(key, value.into_value());
+        if otel::records_on_creation() {
+            otel::set_span_attribute(field.clone());
+        }
+        self.fields.push(field);
+        self
+    }
+
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+
+    // Every frame of the captured backtrace, without any filtering.
+    pub fn frames(&self) -> Vec<Frame> {
+        self.backtrace().map(backtrace::frames).unwrap_or_default()
+    }
+
+    pub fn fields(&self) -> &[KeyValue] {
+        &self.fields
+    }
+
+    pub fn kind(&self) -> Option<&ErrorKind> {
+        self.kind.as_deref()
+    }
+
+    pub fn span_trace(&self) -> &SpanTrace {
+        &self.context
+    }
+
+    // False when the error was created outside of any span, or when the
+    // subscriber has no `ErrorLayer` to capture the span trace with.
+    pub fn has_span_context(&self) -> bool {
+        self.context.status() == SpanTraceStatus::CAPTURED
+    }
 }
 
 impl<T: fmt::Debug + fmt::Display> fmt::Display for TrasyError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Error: {}\nContext: {}\n", self.inner, self.context)?;
+        match &self.kind {
+            Some(kind) => {
+                writeln!(f, "Error[{}]: {}", kind.code, self.inner)?;
+                writeln!(f, "Category: {}", kind.category)?;
+                if let Some(doc_url) = kind.doc_url {
+                    writeln!(f, "Documentation: {}", doc_url)?;
+                }
+            }
+            None => writeln!(f, "Error: {}", self.inner)?,
+        }
+        write!(
+            f,
+            "Location: {}\nContext: {}\n",
+            self.location, self.context
+        )?;
+        if !self.fields.is_empty() {
+            write!(f, "Fields:")?;
+            for field in &self.fields {
+                write!(f, " {}={}", field.key, field.value)?;
+            }
+            writeln!(f)?;
+        }
         if let Some(ref backtrace) = self.backtrace {
-            write!(f, "Backtrace: {:?}\n", backtrace)?;
+            writeln!(f, "Backtrace:")?;
+            backtrace::with_frame_filter(|filter| write!(f, "{}", filter.render(backtrace)))?;
         }
         Ok(())
     }
 }
 
-impl<T: fmt::Debug + fmt::Display + Error + AsRef<dyn Error>> Error for TrasyError<T> {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        Some(self.inner.as_ref())
+impl<T: StdError + 'static> StdError for TrasyError<T> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.inner)
     }
 }
 
+// A literal followed only by `key = value` pairs is a message with fields, not
+// a format string with named arguments. Inline captures like `"{path}"` still
+// work in the message, so `error!("{x}", x = 1)` formats a local `x` and adds
+// the field `x`; without a local `x` it fails with "cannot find value `x`".
 #[macro_export]
 macro_rules! error {
-    ($e:expr) => {
-        TrasyError::new($e).with_backtrace(std::backtrace::Backtrace::capture())
+    ($msg:literal $(,)?) => {
+        $crate::TrasyError::new($crate::DynError::msg(format!($msg)))
+    };
+    ($msg:literal, $($key:ident = $value:expr),+ $(,)?) => {
+        $crate::error!($msg)
+            $(.with_field(stringify!($key), $value))+
+    };
+    ($fmt:literal, $($arg:tt)+) => {
+        $crate::TrasyError::new($crate::DynError::msg(format!($fmt, $($arg)+)))
+    };
+    ($e:expr, $($key:ident = $value:expr),+ $(,)?) => {
+        $crate::error!($e)
+            $(.with_field(stringify!($key), $value))+
+    };
+    ($e:expr $(,)?) => {
+        match $e {
+            error => {
+                #[allow(unused_imports)]
+                use $crate::__private::{KindedTag as _, PlainTag as _};
+                (&error).trasy_kind_tag().wrap(error)
+            }
+        }
     };
 }
 
 #[macro_export]
 macro_rules! bail {
-    ($e:expr) => {
-        Err(TrasyError::new($e).with_backtrace(std::backtrace::Backtrace::capture()))
+    ($($arg:tt)+) => {
+        Err($crate::error!($($arg)+))
     };
 }
 
-struct TelemetryConfig {
-    service_name: String,
-    #[allow(dead_code)]
-    endpoint: String,
-    use_batch: bool, // Determine whether to use batch or simple span processing
-    oltp_exporter: Option<SpanExporterBuilder>,
-}
-
-impl TelemetryConfig {
-    #[allow(dead_code)]
-    pub fn with_oltp_exporter<B: Into<SpanExporterBuilder>>(mut self, exporter: B) -> Self {
-        self.oltp_exporter = Some(exporter.into());
-        self
-    }
-}
-
-impl Default for TelemetryConfig {
-    fn default() -> Self {
-        let endpoint = "http://localhost:4318";
-
-        let otlp_exporter = opentelemetry_otlp::new_exporter()
-            .http()
-            .with_endpoint(endpoint);
-
-        Self {
-            service_name: "default-service".to_string(),
-            endpoint: endpoint.to_string(),
-            use_batch: true,
-            oltp_exporter: Some(otlp_exporter.into()),
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            return Err($crate::TrasyError::new($crate::DynError::msg(
+                String::from(concat!("Condition failed: `", stringify!($cond), "`")),
+            )));
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            return $crate::bail!($($arg)+);
         }
-    }
-}
-
-#[allow(dead_code)]
-async fn setup_opentelemetry(
-    config: TelemetryConfig,
-) -> Result<OpenTelemetryLayer<Registry, Tracer>, TrasyError<std::io::Error>> {
-    let Some(exporter) = config.oltp_exporter else {
-        return Err(TrasyError::new(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            "oltp_exporter is None",
-        )));
     };
-
-    let service_name = config.service_name;
-
-    let builder = opentelemetry_otlp::new_pipeline()
-        .tracing()
-        .with_exporter(exporter)
-        .with_trace_config(
-            trace::config().with_resource(Resource::new(vec![KeyValue::new(
-                "service.name",
-                service_name,
-            )])),
-        );
-
-    let tracer = if config.use_batch {
-        builder.install_batch(opentelemetry_sdk::runtime::Tokio)
-    } else {
-        builder.install_simple()
-    }
-    .expect("Error initializing OpenTelemetry exporter");
-
-    let opentelemetry: OpenTelemetryLayer<Registry, Tracer> =
-        tracing_opentelemetry::layer().with_tracer(tracer);
-    Ok(opentelemetry)
 }
@@ -0,0 +1,149 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::Cell;
+use std::fmt;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+use opentelemetry::trace::{Event, SpanContext, Status, TraceContextExt};
+use opentelemetry::KeyValue;
+use tracing_opentelemetry::{OpenTelemetrySpanExt, OtelData};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Registry;
+
+use crate::TrasyError;
+
+static RECORD_ON_CREATION: AtomicBool = AtomicBool::new(true);
+
+// Whether errors are recorded on the current span when they are created, which
+// is the default. With `false`, only `TrasyError::record` and the axum, tonic
+// and `Report` integrations record them, so errors that are handled right away
+// leave no trace on the span.
+pub fn set_record_on_creation(enabled: bool) {
+    RECORD_ON_CREATION.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn records_on_creation() -> bool {
+    RECORD_ON_CREATION.load(Ordering::Relaxed)
+}
+
+pub(crate) fn record_on_creation<T: fmt::Display>(error: &TrasyError<T>) {
+    if records_on_creation() {
+        record_exception(error);
+    }
+}
+
+thread_local! {
+    static IN_OTEL_DATA: Cell<bool> = const { Cell::new(false) };
+}
+
+// Clears `IN_OTEL_DATA` even when `f` unwinds.
+struct ResetInOtelData;
+
+impl Drop for ResetInOtelData {
+    fn drop(&mut self) {
+        IN_OTEL_DATA.with(|in_otel_data| in_otel_data.set(false));
+    }
+}
+
+// Gives access to the OpenTelemetry span data that `OpenTelemetryLayer` keeps
+// in the extensions of the current tracing span. Does nothing when there is no
+// current span or the subscriber is not built on top of `Registry`.
+//
+// Also does nothing when called from within the subscriber, e.g. by the panic
+// hook for a panic in a layer, since the extensions may already be borrowed.
+// `get_default` hands out a no-op dispatcher in that case, and `IN_OTEL_DATA`
+// covers panics in `f` itself.
+pub(crate) fn with_current_otel_data<F: FnOnce(&mut OtelData)>(f: F) {
+    if IN_OTEL_DATA.with(Cell::get) {
+        return;
+    }
+    let mut f = Some(f);
+    tracing::dispatcher::get_default(|dispatch| {
+        let Some(registry) = dispatch.downcast_ref::<Registry>() else {
+            return;
+        };
+        let Some(id) = dispatch.current_span().id().cloned() else {
+            return;
+        };
+        let Some(span) = registry.span(&id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let (Some(data), Some(f)) = (extensions.get_mut::<OtelData>(), f.take()) {
+            IN_OTEL_DATA.with(|in_otel_data| in_otel_data.set(true));
+            let _reset = ResetInOtelData;
+            f(data);
+        }
+    });
+}
+
+// Span context of the current span, when it is exported through
+// `OpenTelemetryLayer`.
+#[cfg_attr(not(any(feature = "axum", feature = "tonic")), allow(dead_code))]
+pub(crate) fn current_span_context() -> Option<SpanContext> {
+    let context = tracing::Span::current().context();
+    let span_context = context.span().span_context().clone();
+    span_context.is_valid().then_some(span_context)
+}
+
+pub(crate) fn set_span_attribute(attribute: KeyValue) {
+    with_current_otel_data(|data| {
+        data.builder
+            .attributes
+            .get_or_insert_with(Vec::new)
+            .push(attribute);
+    });
+}
+
+// Records the error as an `exception` event following the OpenTelemetry
+// semantic conventions, with its fields as extra attributes, and marks the
+// current span as failed. The code of its kind becomes `error.type`.
+pub(crate) fn record_exception<T: fmt::Display>(error: &TrasyError<T>) {
+    if let Some(kind) = &error.kind {
+        set_span_attribute(KeyValue::new("error.type", kind.code));
+    }
+    record_exception_event(
+        std::any::type_name::<T>(),
+        &error.inner,
+        error.location,
+        error.backtrace.as_deref(),
+        &error.fields,
+    );
+}
+
+pub(crate) fn record_exception_event(
+    exception_type: &'static str,
+    message: &dyn fmt::Display,
+    location: &Location<'_>,
+    backtrace: Option<&Backtrace>,
+    fields: &[KeyValue],
+) {
+    with_current_otel_data(|data| {
+        let message = message.to_string();
+        let mut attributes = vec![
+            KeyValue::new("exception.type", exception_type),
+            KeyValue::new("exception.message", message.clone()),
+            KeyValue::new("code.filepath", location.file().to_string()),
+            KeyValue::new("code.lineno", i64::from(location.line())),
+            KeyValue::new("code.column", i64::from(location.column())),
+        ];
+        attributes.extend_from_slice(fields);
+        if let Some(backtrace) = backtrace {
+            if backtrace.status() == BacktraceStatus::Captured {
+                attributes.push(KeyValue::new("exception.stacktrace", backtrace.to_string()));
+            }
+        }
+
+        let builder = &mut data.builder;
+        builder.events.get_or_insert_with(Vec::new).push(Event::new(
+            "exception",
+            SystemTime::now(),
+            attributes,
+            0,
+        ));
+        if builder.status == Status::Unset {
+            builder.status = Status::error(message);
+        }
+    });
+}
@@ -0,0 +1,51 @@
+use std::any::Any;
+use std::panic::{self, PanicHookInfo};
+use std::thread;
+
+use opentelemetry::KeyValue;
+use tracing_error::SpanTrace;
+
+use crate::{backtrace, otel, telemetry, ErrorReport};
+
+// Replaces the current panic hook. A panic is printed as a `TrasyError`-style
+// report with its location, span trace and backtrace, recorded as an
+// `exception` event on the current OpenTelemetry span, and the exporter set up
+// by `setup_opentelemetry` is flushed before unwinding continues.
+//
+// The previous hook does not run: std offers no way to tell its default hook
+// apart from a custom one, and the default hook would print the panic a second
+// time. Crash reporters that chain to the previous hook can be installed
+// afterwards.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(panic_hook));
+}
+
+fn panic_hook(info: &PanicHookInfo<'_>) {
+    let message = payload_message(info.payload());
+    let context = SpanTrace::capture();
+    let backtrace = backtrace::capture_global();
+    let thread = thread::current();
+    let fields = [KeyValue::new(
+        "thread.name",
+        thread.name().unwrap_or("<unnamed>").to_string(),
+    )];
+
+    if let Some(location) = info.location() {
+        otel::record_exception_event("panic", &message, location, backtrace.as_ref(), &fields);
+        let report = ErrorReport::panic(&message, location, &fields, &context, backtrace.as_ref());
+        eprint!("{}", report);
+    } else {
+        eprintln!("Panic: {}", message);
+    }
+    telemetry::flush_on_panic();
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
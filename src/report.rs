@@ -0,0 +1,218 @@
+use std::backtrace::Backtrace;
+use std::error::Error as StdError;
+use std::fmt;
+use std::panic::Location;
+
+use opentelemetry::KeyValue;
+use tracing_error::SpanTrace;
+
+use crate::backtrace::with_frame_filter;
+use crate::span::split_fields;
+use crate::style::{paint, use_color, BOLD, CYAN, DIM, GREEN, MAGENTA, RED, YELLOW};
+use crate::{Error, ErrorKind, TrasyError};
+
+// Human readable report of a `TrasyError`: the message, the `source()` chain,
+// the fields, the span trace and the filtered backtrace.
+pub struct ErrorReport<'a> {
+    message: &'a dyn fmt::Display,
+    location: &'a Location<'a>,
+    kind: Option<&'a ErrorKind>,
+    source: Option<&'a (dyn StdError + 'static)>,
+    fields: &'a [KeyValue],
+    context: &'a SpanTrace,
+    backtrace: Option<&'a Backtrace>,
+    color: bool,
+    label: Option<&'static str>,
+}
+
+impl<'a> ErrorReport<'a> {
+    pub(crate) fn panic(
+        message: &'a dyn fmt::Display,
+        location: &'a Location<'a>,
+        fields: &'a [KeyValue],
+        context: &'a SpanTrace,
+        backtrace: Option<&'a Backtrace>,
+    ) -> Self {
+        Self {
+            message,
+            location,
+            kind: None,
+            source: None,
+            fields,
+            context,
+            backtrace,
+            color: use_color(),
+            label: Some("Panic:"),
+        }
+    }
+
+    fn new<T: fmt::Display>(
+        error: &'a TrasyError<T>,
+        source: Option<&'a (dyn StdError + 'static)>,
+    ) -> Self {
+        Self {
+            message: &error.inner,
+            location: error.location,
+            kind: error.kind(),
+            source,
+            fields: &error.fields,
+            context: &error.context,
+            backtrace: error.backtrace(),
+            color: use_color(),
+            label: Some("Error:"),
+        }
+    }
+
+    // Overrides the terminal and `NO_COLOR` detection.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    // Leaves out the `Error:` label, for callers that print their own.
+    pub(crate) fn without_label(mut self) -> Self {
+        self.label = None;
+        self
+    }
+
+    fn write_causes(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(source) = self.source else {
+            return Ok(());
+        };
+
+        writeln!(f, "\n{}", paint("Caused by:", BOLD, self.color))?;
+        let causes = std::iter::successors(Some(source), |&cause| cause.source());
+        for (index, cause) in causes.enumerate() {
+            writeln!(f, "{:>4}: {}", index, paint(cause, YELLOW, self.color))?;
+        }
+        Ok(())
+    }
+
+    fn write_fields(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fields.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f, "\n{}", paint("Fields:", BOLD, self.color))?;
+        for field in self.fields {
+            writeln!(
+                f,
+                "      {}={}",
+                paint(&field.key, MAGENTA, self.color),
+                field.value
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_span_trace(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut result = Ok(());
+        let mut index = 0;
+        self.context.with_spans(|metadata, fields| {
+            if index == 0 {
+                result = writeln!(f, "\n{}", paint("Span trace:", BOLD, self.color));
+            }
+            result = result
+                .and_then(|_| {
+                    write!(
+                        f,
+                        "{:>4}: {}::{}",
+                        index,
+                        metadata.target(),
+                        paint(metadata.name(), GREEN, self.color)
+                    )
+                })
+                .and_then(|_| match fields.is_empty() {
+                    true => Ok(()),
+                    false => write!(f, " with {}", HighlightFields(fields, self.color)),
+                })
+                .and_then(|_| match (metadata.file(), metadata.line()) {
+                    (Some(file), Some(line)) => {
+                        let location = format!("{}:{}", file, line);
+                        write!(f, "\n        at {}", paint(location, DIM, self.color))
+                    }
+                    _ => Ok(()),
+                })
+                .and_then(|_| writeln!(f));
+            index += 1;
+            result.is_ok()
+        });
+        result
+    }
+
+    fn write_backtrace(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(backtrace) = self.backtrace else {
+            return Ok(());
+        };
+
+        writeln!(f, "\n{}", paint("Backtrace:", BOLD, self.color))?;
+        with_frame_filter(|filter| write!(f, "{}", filter.render(backtrace).color(self.color)))
+    }
+}
+
+impl fmt::Display for ErrorReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.label, self.kind) {
+            (Some(label), Some(kind)) => {
+                let label = format!("{}[{}]:", label.trim_end_matches(':'), kind.code);
+                write!(f, "{} ", paint(label, RED, self.color))?;
+            }
+            (Some(label), None) => write!(f, "{} ", paint(label, RED, self.color))?,
+            (None, _) => {}
+        }
+        writeln!(f, "{}", paint(self.message, BOLD, self.color))?;
+        writeln!(f, "    at {}", paint(self.location, DIM, self.color))?;
+        if let Some(kind) = self.kind {
+            writeln!(f, "    category {}", kind.category)?;
+            if let Some(doc_url) = kind.doc_url {
+                writeln!(f, "    see {}", paint(doc_url, CYAN, self.color))?;
+            }
+        }
+        self.write_causes(f)?;
+        self.write_fields(f)?;
+        self.write_span_trace(f)?;
+        self.write_backtrace(f)
+    }
+}
+
+impl fmt::Debug for ErrorReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// Highlights the keys of the `key=value` pairs recorded by `ErrorLayer`.
+struct HighlightFields<'a>(&'a str, bool);
+
+impl fmt::Display for HighlightFields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let HighlightFields(fields, color) = *self;
+        let mut pairs = split_fields(fields).peekable();
+        let Some((first, _)) = pairs.peek().filter(|_| color) else {
+            return f.write_str(fields);
+        };
+
+        // Anything before the first key is written as is.
+        let offset = first.as_ptr() as usize - fields.as_ptr() as usize;
+        f.write_str(&fields[..offset])?;
+        for (index, (key, value)) in pairs.enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", paint(key, CYAN, true), value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: StdError + 'static> TrasyError<T> {
+    pub fn report(&self) -> ErrorReport<'_> {
+        ErrorReport::new(self, self.inner.source())
+    }
+}
+
+impl Error {
+    pub fn report(&self) -> ErrorReport<'_> {
+        ErrorReport::new(self, self.inner.source())
+    }
+}
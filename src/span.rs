@@ -0,0 +1,232 @@
+use std::borrow::Cow;
+
+use tracing::Metadata;
+
+use crate::TrasyError;
+
+// One span of the `SpanTrace` captured by a `TrasyError`.
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    metadata: &'static Metadata<'static>,
+    fields: Vec<(String, String)>,
+}
+
+impl SpanRecord {
+    pub fn name(&self) -> &'static str {
+        self.metadata.name()
+    }
+
+    pub fn target(&self) -> &'static str {
+        self.metadata.target()
+    }
+
+    pub fn module_path(&self) -> Option<&'static str> {
+        self.metadata.module_path()
+    }
+
+    pub fn file(&self) -> Option<&'static str> {
+        self.metadata.file()
+    }
+
+    pub fn line(&self) -> Option<u32> {
+        self.metadata.line()
+    }
+
+    pub fn metadata(&self) -> &'static Metadata<'static> {
+        self.metadata
+    }
+
+    // Values are the text recorded by `ErrorLayer`. `Debug`-formatted strings
+    // lose their quotes and escapes.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+    }
+}
+
+impl<T> TrasyError<T> {
+    // Spans that were active when the error was created, innermost first.
+    pub fn spans(&self) -> impl Iterator<Item = SpanRecord> {
+        let mut spans = Vec::new();
+        self.context.with_spans(|metadata, fields| {
+            spans.push(SpanRecord {
+                metadata,
+                fields: parse_fields(fields)
+                    .map(|(key, value)| (key.to_string(), value.into_owned()))
+                    .collect(),
+            });
+            true
+        });
+        spans.into_iter()
+    }
+}
+
+// Splits the `key=value key=value` text that `ErrorLayer` records for a span.
+// A new pair starts at every `key=` that follows a space outside of a quoted
+// string, so values may contain spaces (e.g. `Debug` output).
+pub(crate) fn parse_fields(fields: &str) -> impl Iterator<Item = (&str, Cow<'_, str>)> {
+    split_fields(fields).map(|(key, value)| (key, unquote(value)))
+}
+
+// Like `parse_fields`, but values are left exactly as recorded.
+pub(crate) fn split_fields(fields: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut starts = Vec::new();
+    let mut at_start = true;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in fields.char_indices() {
+        if at_start && !quoted && is_key_at(&fields[index..]) {
+            starts.push(index);
+        }
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ => {}
+        }
+        at_start = c == ' ';
+    }
+
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .map(|&next| next - 1)
+        .chain(Some(fields.len()))
+        .collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .filter_map(|(start, end)| fields[start..end].split_once('='))
+}
+
+fn is_key_at(text: &str) -> bool {
+    let len = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(text.len());
+    len > 0 && text[len..].starts_with('=')
+}
+
+fn unquote(value: &str) -> Cow<'_, str> {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(quoted) if quoted.contains('\\') => Cow::Owned(unescape(quoted)),
+        Some(quoted) => Cow::Borrowed(quoted),
+        None => Cow::Borrowed(value),
+    }
+}
+
+// Reverses the escapes of `str::escape_debug`, which `Debug` uses for strings.
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('0') => result.push('\0'),
+            Some('u') => {
+                let rest = chars.as_str();
+                let escape = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .and_then(|(hex, _)| Some((hex, u32::from_str_radix(hex, 16).ok()?)))
+                    .and_then(|(hex, code)| Some((hex, char::from_u32(code)?)));
+                match escape {
+                    Some((hex, c)) => {
+                        result.push(c);
+                        chars = rest[hex.len() + 2..].chars();
+                    }
+                    None => result.push_str("\\u"),
+                }
+            }
+            Some(c) => result.push(c),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields() {
+        let cases: &[(&str, &[(&str, &str)])] = &[
+            ("", &[]),
+            ("request_id=42", &[("request_id", "42")]),
+            (r#"id=1 user="ann""#, &[("id", "1"), ("user", "ann")]),
+            ("empty= next=1", &[("empty", ""), ("next", "1")]),
+            (
+                r#"path="/srv/my app" id=1"#,
+                &[("path", "/srv/my app"), ("id", "1")],
+            ),
+            (
+                r#"query="a=1 b=2" id=1"#,
+                &[("query", "a=1 b=2"), ("id", "1")],
+            ),
+            (
+                "http.method=GET http.status_code=200",
+                &[("http.method", "GET"), ("http.status_code", "200")],
+            ),
+            (
+                r#"user=User { id: 1, name: "ann lee" } id=7"#,
+                &[("user", r#"User { id: 1, name: "ann lee" }"#), ("id", "7")],
+            ),
+            ("list=[1, 2] n=2", &[("list", "[1, 2]"), ("n", "2")]),
+            (
+                "url=http://a/?b=c id=1",
+                &[("url", "http://a/?b=c"), ("id", "1")],
+            ),
+        ];
+        for (input, expected) in cases {
+            let parsed: Vec<(&str, Cow<'_, str>)> = parse_fields(input).collect();
+            let parsed: Vec<(&str, &str)> = parsed
+                .iter()
+                .map(|(key, value)| (*key, value.as_ref()))
+                .collect();
+            assert_eq!(parsed, *expected, "input `{}`", input);
+        }
+    }
+
+    #[test]
+    fn unescapes_debug_strings() {
+        let cases = [
+            (r#"msg="say \"hi\" id=1" n=2"#, r#"say "hi" id=1"#),
+            (r#"msg="C:\\dir" n=2"#, r"C:\dir"),
+            (r#"msg="a\nb\tc" n=2"#, "a\nb\tc"),
+            (r#"msg="caf\u{e9} \u{1f600}" n=2"#, "caf\u{e9} \u{1f600}"),
+            (r#"msg="bad \u{zz}" n=2"#, r"bad \u{zz}"),
+        ];
+        for (input, expected) in cases {
+            let mut fields = parse_fields(input);
+            assert_eq!(
+                fields.next().map(|(_, value)| value),
+                Some(Cow::Borrowed(expected)),
+                "input `{}`",
+                input
+            );
+            assert_eq!(fields.next().map(|(key, _)| key), Some("n"));
+        }
+    }
+
+    #[test]
+    fn split_keeps_values_as_recorded() {
+        let fields: Vec<_> = split_fields(r#"user="ann" note="a \"b\"""#).collect();
+        assert_eq!(fields, [("user", r#""ann""#), ("note", r#""a \"b\"""#)]);
+    }
+}
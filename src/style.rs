@@ -0,0 +1,43 @@
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+// ANSI styles shared by the error report and the rendered backtrace.
+pub(crate) const RED: &str = "\x1b[31m";
+pub(crate) const GREEN: &str = "\x1b[32m";
+pub(crate) const YELLOW: &str = "\x1b[33m";
+pub(crate) const MAGENTA: &str = "\x1b[35m";
+pub(crate) const CYAN: &str = "\x1b[36m";
+pub(crate) const BOLD: &str = "\x1b[1m";
+pub(crate) const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+// Colors are used when stderr is a terminal and `NO_COLOR` is not set.
+pub(crate) fn use_color() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !no_color && io::stderr().is_terminal()
+}
+
+pub(crate) struct Paint<D> {
+    value: D,
+    style: &'static str,
+    enabled: bool,
+}
+
+impl<D: fmt::Display> fmt::Display for Paint<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.enabled {
+            write!(f, "{}{}{}", self.style, self.value, RESET)
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+pub(crate) fn paint<D: fmt::Display>(value: D, style: &'static str, enabled: bool) -> Paint<D> {
+    Paint {
+        value,
+        style,
+        enabled,
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::env;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::trace::{BatchConfigBuilder, Sampler};
+
+use super::{invalid_input, validate_endpoint, Protocol, TelemetryConfig};
+use crate::TrasyError;
+
+const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+const OTEL_EXPORTER_OTLP_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+const OTEL_EXPORTER_OTLP_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_PROTOCOL";
+const OTEL_EXPORTER_OTLP_HEADERS: &str = "OTEL_EXPORTER_OTLP_HEADERS";
+const OTEL_RESOURCE_ATTRIBUTES: &str = "OTEL_RESOURCE_ATTRIBUTES";
+const OTEL_TRACES_SAMPLER: &str = "OTEL_TRACES_SAMPLER";
+const OTEL_TRACES_SAMPLER_ARG: &str = "OTEL_TRACES_SAMPLER_ARG";
+const OTEL_BSP_SCHEDULE_DELAY: &str = "OTEL_BSP_SCHEDULE_DELAY";
+const OTEL_BSP_EXPORT_TIMEOUT: &str = "OTEL_BSP_EXPORT_TIMEOUT";
+const OTEL_BSP_MAX_QUEUE_SIZE: &str = "OTEL_BSP_MAX_QUEUE_SIZE";
+const OTEL_BSP_MAX_EXPORT_BATCH_SIZE: &str = "OTEL_BSP_MAX_EXPORT_BATCH_SIZE";
+const OTEL_BSP_MAX_CONCURRENT_EXPORTS: &str = "OTEL_BSP_MAX_CONCURRENT_EXPORTS";
+
+impl TelemetryConfig {
+    // Unset variables keep their default value. Unlike the OpenTelemetry SDK,
+    // which silently falls back to defaults, invalid values are reported.
+    pub fn from_env() -> Result<Self, TrasyError<io::Error>> {
+        let mut config = TelemetryConfig::default();
+
+        if let Some(attributes) = var(OTEL_RESOURCE_ATTRIBUTES) {
+            for (key, value) in parse_key_values(OTEL_RESOURCE_ATTRIBUTES, &attributes)? {
+                if key == "service.name" {
+                    config.service_name = value;
+                } else {
+                    config.resource_attributes.push(KeyValue::new(key, value));
+                }
+            }
+        }
+
+        // OTEL_SERVICE_NAME takes precedence over `service.name` in the resource attributes.
+        if let Some(service_name) = var(OTEL_SERVICE_NAME) {
+            config.service_name = service_name;
+        }
+
+        if let Some(protocol) = var(OTEL_EXPORTER_OTLP_PROTOCOL) {
+            config.protocol = parse_protocol(&protocol)?;
+        }
+
+        if let Some(endpoint) = var(OTEL_EXPORTER_OTLP_ENDPOINT) {
+            validate_endpoint(&endpoint)?;
+            config.endpoint = Some(endpoint);
+        }
+
+        if let Some(headers) = var(OTEL_EXPORTER_OTLP_HEADERS) {
+            config.headers = parse_key_values(OTEL_EXPORTER_OTLP_HEADERS, &headers)?
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+        }
+
+        if let Some(sampler) = var(OTEL_TRACES_SAMPLER) {
+            config.sampler = parse_sampler(&sampler, var(OTEL_TRACES_SAMPLER_ARG))?;
+        }
+
+        let mut batch = BatchConfigBuilder::default();
+        if let Some(delay) = parse_var::<u64>(OTEL_BSP_SCHEDULE_DELAY)? {
+            batch = batch.with_scheduled_delay(Duration::from_millis(delay));
+        }
+        if let Some(timeout) = parse_var::<u64>(OTEL_BSP_EXPORT_TIMEOUT)? {
+            batch = batch.with_max_export_timeout(Duration::from_millis(timeout));
+        }
+        if let Some(size) = parse_var::<usize>(OTEL_BSP_MAX_QUEUE_SIZE)? {
+            batch = batch.with_max_queue_size(size);
+        }
+        if let Some(size) = parse_var::<usize>(OTEL_BSP_MAX_EXPORT_BATCH_SIZE)? {
+            batch = batch.with_max_export_batch_size(size);
+        }
+        if let Some(exports) = parse_var::<usize>(OTEL_BSP_MAX_CONCURRENT_EXPORTS)? {
+            batch = batch.with_max_concurrent_exports(exports);
+        }
+        config.batch_config = batch.build();
+
+        Ok(config)
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_var<T: FromStr>(name: &str) -> Result<Option<T>, TrasyError<io::Error>> {
+    match var(name) {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| invalid_input(format!("{}: invalid value `{}`", name, value))),
+        None => Ok(None),
+    }
+}
+
+fn parse_protocol(protocol: &str) -> Result<Protocol, TrasyError<io::Error>> {
+    match protocol {
+        "http/protobuf" => Ok(Protocol::HttpBinary),
+        #[cfg(feature = "otlp-http-json")]
+        "http/json" => Ok(Protocol::HttpJson),
+        #[cfg(not(feature = "otlp-http-json"))]
+        "http/json" => Err(invalid_input(format!(
+            "{}: the http/json protocol requires the `otlp-http-json` feature",
+            OTEL_EXPORTER_OTLP_PROTOCOL
+        ))),
+        #[cfg(feature = "otlp-grpc")]
+        "grpc" => Ok(Protocol::Grpc),
+        #[cfg(not(feature = "otlp-grpc"))]
+        "grpc" => Err(invalid_input(format!(
+            "{}: the grpc protocol requires the `otlp-grpc` feature",
+            OTEL_EXPORTER_OTLP_PROTOCOL
+        ))),
+        other => Err(invalid_input(format!(
+            "{}: unsupported protocol `{}`",
+            OTEL_EXPORTER_OTLP_PROTOCOL, other
+        ))),
+    }
+}
+
+fn parse_sampler(sampler: &str, arg: Option<String>) -> Result<Sampler, TrasyError<io::Error>> {
+    let ratio = || -> Result<f64, TrasyError<io::Error>> {
+        match &arg {
+            Some(arg) => match arg.parse::<f64>() {
+                Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+                _ => Err(invalid_input(format!(
+                    "{}: `{}` is not a ratio between 0 and 1",
+                    OTEL_TRACES_SAMPLER_ARG, arg
+                ))),
+            },
+            None => Ok(1.0),
+        }
+    };
+
+    let sampler = match sampler {
+        "always_on" => Sampler::AlwaysOn,
+        "always_off" => Sampler::AlwaysOff,
+        "traceidratio" => Sampler::TraceIdRatioBased(ratio()?),
+        "parentbased_always_on" => Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+        "parentbased_always_off" => Sampler::ParentBased(Box::new(Sampler::AlwaysOff)),
+        "parentbased_traceidratio" => {
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio()?)))
+        }
+        other => {
+            return Err(invalid_input(format!(
+                "{}: unsupported sampler `{}`",
+                OTEL_TRACES_SAMPLER, other
+            )))
+        }
+    };
+
+    Ok(sampler)
+}
+
+// Parses the `key1=value1,key2=value2` format shared by the headers and
+// resource attributes variables. Values may be percent-encoded.
+fn parse_key_values(
+    name: &str,
+    input: &str,
+) -> Result<Vec<(String, String)>, TrasyError<io::Error>> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .filter(|(key, _)| !key.trim().is_empty())
+                .ok_or_else(|| {
+                    invalid_input(format!("{}: `{}` is not a key=value pair", name, pair))
+                })?;
+            let value = percent_decode(value.trim()).ok_or_else(|| {
+                invalid_input(format!(
+                    "{}: `{}` is not valid percent-encoding",
+                    name, value
+                ))
+            })?;
+            Ok((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            // `from_str_radix` alone would also accept a sign, as in `%+1`.
+            let hex = input
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_values() {
+        let cases: &[(&str, &[(&str, &str)])] = &[
+            ("", &[]),
+            ("a=1", &[("a", "1")]),
+            (" a = 1 , b=2,", &[("a", "1"), ("b", "2")]),
+            ("empty=", &[("empty", "")]),
+            ("auth=Bearer%20abc%3D%3D", &[("auth", "Bearer abc==")]),
+            ("team=caf%C3%A9", &[("team", "café")]),
+            ("url=a=b", &[("url", "a=b")]),
+        ];
+        for (input, expected) in cases {
+            let parsed = parse_key_values("TEST", input).unwrap();
+            let expected: Vec<(String, String)> = expected
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            assert_eq!(parsed, expected, "input `{}`", input);
+        }
+    }
+
+    #[test]
+    fn malformed_key_values() {
+        for input in ["novalue", "=1", "a=1,b", " =x", "a=%zz", "a=%4", "a=%ff"] {
+            assert!(
+                parse_key_values("TEST", input).is_err(),
+                "input `{}`",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn percent_decoding() {
+        let cases = [
+            ("plain", Some("plain")),
+            ("", Some("")),
+            ("a%2Cb", Some("a,b")),
+            ("%41%62", Some("Ab")),
+            ("100%", None),
+            ("%g0", None),
+            ("%+1", None),
+            ("%C3", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                percent_decode(input).as_deref(),
+                expected,
+                "input `{}`",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn samplers() {
+        let cases = [
+            ("always_on", None, "AlwaysOn"),
+            ("always_off", None, "AlwaysOff"),
+            ("traceidratio", None, "TraceIdRatioBased(1.0)"),
+            ("traceidratio", Some("0.25"), "TraceIdRatioBased(0.25)"),
+            ("parentbased_always_on", None, "ParentBased(AlwaysOn)"),
+            ("parentbased_always_off", None, "ParentBased(AlwaysOff)"),
+            (
+                "parentbased_traceidratio",
+                Some("0"),
+                "ParentBased(TraceIdRatioBased(0.0))",
+            ),
+        ];
+        for (sampler, arg, expected) in cases {
+            let parsed = parse_sampler(sampler, arg.map(String::from)).unwrap();
+            assert_eq!(format!("{:?}", parsed), expected, "sampler `{}`", sampler);
+        }
+    }
+
+    #[test]
+    fn invalid_samplers() {
+        let cases = [
+            ("traceidratio", Some("half")),
+            ("traceidratio", Some("1.5")),
+            ("parentbased_traceidratio", Some("-0.1")),
+            ("sometimes", None),
+            ("", None),
+        ];
+        for (sampler, arg) in cases {
+            let parsed = parse_sampler(sampler, arg.map(String::from));
+            assert!(parsed.is_err(), "sampler `{}` with {:?}", sampler, arg);
+        }
+    }
+
+    #[test]
+    fn protocols() {
+        assert_eq!(
+            parse_protocol("http/protobuf").unwrap(),
+            Protocol::HttpBinary
+        );
+        #[cfg(feature = "otlp-grpc")]
+        assert_eq!(parse_protocol("grpc").unwrap(), Protocol::Grpc);
+        #[cfg(not(feature = "otlp-grpc"))]
+        assert!(parse_protocol("grpc").is_err());
+        #[cfg(feature = "otlp-http-json")]
+        assert_eq!(parse_protocol("http/json").unwrap(), Protocol::HttpJson);
+        #[cfg(not(feature = "otlp-http-json"))]
+        assert!(parse_protocol("http/json").is_err());
+
+        for protocol in ["http", "HTTP/PROTOBUF", "thrift", ""] {
+            assert!(parse_protocol(protocol).is_err(), "protocol `{}`", protocol);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::io;
+use std::sync::{mpsc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use opentelemetry::global;
+use opentelemetry_sdk::trace::TracerProvider;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::TrasyError;
+
+pub(crate) const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The provider of the latest guard, flushed by the panic hook. Cleared when the
+// guard shuts down.
+static PANIC_FLUSH: Mutex<Option<(TracerProvider, Duration)>> = Mutex::new(None);
+
+// Flushes pending spans and shuts the tracer provider down when dropped, so
+// short-lived programs do not lose their last batch. Keep it alive until the
+// end of `main`.
+#[must_use = "dropping the guard immediately shuts down telemetry"]
+pub struct TelemetryGuard {
+    provider: Option<TracerProvider>,
+    timeout: Duration,
+}
+
+impl TelemetryGuard {
+    pub(crate) fn new(provider: Option<TracerProvider>, timeout: Duration) -> Self {
+        if let Some(provider) = &provider {
+            *lock_panic_flush() = Some((provider.clone(), timeout));
+        }
+        Self { provider, timeout }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn force_flush(&self) -> Result<(), TrasyError<io::Error>> {
+        match &self.provider {
+            Some(provider) => check_results(provider.force_flush()),
+            None => Ok(()),
+        }
+    }
+
+    // Preferred over relying on `Drop` inside async code: the flush runs on a
+    // blocking thread, so the batch processor task can keep making progress
+    // even on a current-thread runtime.
+    pub async fn shutdown(mut self) -> Result<(), TrasyError<io::Error>> {
+        let Some(provider) = self.provider.take() else {
+            return Ok(());
+        };
+
+        let flush = tokio::task::spawn_blocking(move || flush_and_shutdown(provider));
+        match tokio::time::timeout(self.timeout, flush).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(TrasyError::new(io::Error::other(e))),
+            Err(_) => Err(timed_out(self.timeout)),
+        }
+    }
+}
+
+// Best effort only, `shutdown` is the reliable way to flush. On a
+// current-thread runtime the batch processor task runs on the thread that is
+// dropping the guard, so waiting for the flush here could never succeed.
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        let Some(provider) = self.provider.take() else {
+            return;
+        };
+
+        let current_thread = Handle::try_current()
+            .is_ok_and(|handle| handle.runtime_flavor() == RuntimeFlavor::CurrentThread);
+        if current_thread {
+            tracing::warn!(
+                "TelemetryGuard dropped on a current-thread runtime, call `shutdown().await` \
+                 to export the remaining spans"
+            );
+            thread::spawn(move || flush_and_shutdown(provider));
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(flush_and_shutdown(provider));
+        });
+        match receiver.recv_timeout(self.timeout) {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => tracing::warn!("failed to shut down telemetry: {}", error.inner),
+            Err(_) => tracing::warn!("{}", timed_out(self.timeout).inner),
+        }
+    }
+}
+
+fn flush_and_shutdown(provider: TracerProvider) -> Result<(), TrasyError<io::Error>> {
+    let result = check_results(provider.force_flush());
+    // The processors are shut down once the last reference to the provider,
+    // ours or the global one, is dropped.
+    drop(provider);
+    lock_panic_flush().take();
+    global::shutdown_tracer_provider();
+    result
+}
+
+// Flushes the provider of the latest guard, waiting at most for its shutdown
+// timeout. The flush runs on a helper thread: on a current-thread runtime the
+// batch processor task can only run once the panicking thread unwinds.
+pub(crate) fn flush_on_panic() {
+    let Some((provider, timeout)) = lock_panic_flush().clone() else {
+        return;
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let flush = thread::Builder::new()
+        .name("trasy-panic-flush".to_string())
+        .spawn(move || {
+            let _ = sender.send(check_results(provider.force_flush()));
+        });
+    if flush.is_err() {
+        return;
+    }
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => eprintln!("failed to flush telemetry: {}", error.inner),
+        Err(_) => eprintln!("telemetry flush did not finish within {:?}", timeout),
+    }
+}
+
+// The hook may run while another thread panicked holding the lock.
+fn lock_panic_flush() -> MutexGuard<'static, Option<(TracerProvider, Duration)>> {
+    PANIC_FLUSH.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn check_results(
+    results: Vec<opentelemetry::trace::TraceResult<()>>,
+) -> Result<(), TrasyError<io::Error>> {
+    let errors = results
+        .into_iter()
+        .filter_map(|result| result.err())
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(TrasyError::new(io::Error::other(errors.join("; "))))
+    }
+}
+
+fn timed_out(timeout: Duration) -> TrasyError<io::Error> {
+    TrasyError::new(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("telemetry shutdown did not finish within {:?}", timeout),
+    ))
+}
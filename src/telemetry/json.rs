@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use opentelemetry::trace::TraceError;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use tokio::runtime::Handle;
+
+// OTLP over HTTP with JSON bodies, which `opentelemetry-otlp` 0.15 does not
+// implement. Requests run on the runtime `setup_opentelemetry` was called on,
+// so the simple span processor, which exports from its own thread, works too.
+#[derive(Debug)]
+pub(crate) struct JsonSpanExporter {
+    client: reqwest::Client,
+    url: String,
+    headers: HeaderMap,
+    runtime: Handle,
+}
+
+impl JsonSpanExporter {
+    pub(crate) fn new(
+        endpoint: &str,
+        headers: HashMap<String, String>,
+    ) -> Result<Self, TraceError> {
+        let mut header_map = HeaderMap::with_capacity(headers.len() + 1);
+        header_map.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (key, value) in headers {
+            let name = HeaderName::from_bytes(key.as_bytes())
+                .map_err(|_| TraceError::from(format!("invalid header name `{}`", key)))?;
+            let value = HeaderValue::from_str(&value)
+                .map_err(|_| TraceError::from(format!("invalid value for header `{}`", key)))?;
+            header_map.insert(name, value);
+        }
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url: format!("{}/v1/traces", endpoint.trim_end_matches('/')),
+            headers: header_map,
+            runtime: Handle::try_current()
+                .map_err(|_| TraceError::from("the http/json exporter needs a tokio runtime"))?,
+        })
+    }
+}
+
+impl SpanExporter for JsonSpanExporter {
+    fn export(
+        &mut self,
+        batch: Vec<SpanData>,
+    ) -> Pin<Box<dyn Future<Output = ExportResult> + Send>> {
+        let request = ExportTraceServiceRequest {
+            resource_spans: batch.into_iter().map(Into::into).collect(),
+        };
+        let body = match serde_json::to_vec(&request) {
+            Ok(body) => body,
+            Err(error) => {
+                return Box::pin(std::future::ready(Err(TraceError::from(error.to_string()))))
+            }
+        };
+
+        let request = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .body(body);
+        let url = self.url.clone();
+        let response = self.runtime.spawn(async move { request.send().await });
+
+        Box::pin(async move {
+            let response = response
+                .await
+                .map_err(|error| TraceError::from(error.to_string()))?
+                .map_err(|error| TraceError::from(error.to_string()))?;
+            if !response.status().is_success() {
+                return Err(TraceError::from(format!(
+                    "OTLP export to {} failed with status {}",
+                    url,
+                    response.status()
+                )));
+            }
+            Ok(())
+        })
+    }
+}
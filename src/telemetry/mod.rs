@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::SpanExporterBuilder;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::export::trace::SpanExporter;
+use opentelemetry_sdk::trace;
+use opentelemetry_sdk::trace::{BatchConfig, BatchSpanProcessor, Sampler, Tracer, TracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::Registry;
+
+use crate::{set_backtrace_policy, BacktracePolicy, TrasyError};
+
+mod env;
+mod guard;
+#[cfg(feature = "otlp-http-json")]
+mod json;
+
+pub(crate) use guard::flush_on_panic;
+pub use guard::TelemetryGuard;
+
+const DEFAULT_SERVICE_NAME: &str = "default-service";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Protocol {
+    #[default]
+    HttpBinary,
+    #[cfg(feature = "otlp-http-json")]
+    HttpJson,
+    #[cfg(feature = "otlp-grpc")]
+    Grpc,
+}
+
+impl Protocol {
+    pub fn default_endpoint(&self) -> &'static str {
+        match self {
+            Protocol::HttpBinary => "http://localhost:4318",
+            #[cfg(feature = "otlp-http-json")]
+            Protocol::HttpJson => "http://localhost:4318",
+            #[cfg(feature = "otlp-grpc")]
+            Protocol::Grpc => "http://localhost:4317",
+        }
+    }
+}
+
+// Built with `builder()`, `from_env()` or `default()`, so every config that
+// reaches `setup_opentelemetry` has been validated.
+pub struct TelemetryConfig {
+    service_name: String,
+    protocol: Protocol,
+    // When `None`, the default endpoint of `protocol` is used.
+    endpoint: Option<String>,
+    use_batch: bool, // Determine whether to use batch or simple span processing
+    batch_config: BatchConfig,
+    headers: HashMap<String, String>,
+    resource_attributes: Vec<KeyValue>,
+    sampler: Sampler,
+    shutdown_timeout: Duration,
+    // Applied globally by `setup_opentelemetry`; `None` keeps the current policy.
+    backtrace_policy: Option<BacktracePolicy>,
+    // When `None`, an OTLP exporter for `protocol` pointing at `endpoint` is used.
+    otlp_exporter: Option<SpanExporterBuilder>,
+}
+
+impl TelemetryConfig {
+    pub fn builder() -> TelemetryConfigBuilder {
+        TelemetryConfigBuilder::default()
+    }
+
+    pub fn with_otlp_exporter<B: Into<SpanExporterBuilder>>(mut self, exporter: B) -> Self {
+        self.otlp_exporter = Some(exporter.into());
+        self
+    }
+
+    #[deprecated(note = "renamed to `with_otlp_exporter`")]
+    pub fn with_oltp_exporter<B: Into<SpanExporterBuilder>>(self, exporter: B) -> Self {
+        self.with_otlp_exporter(exporter)
+    }
+
+    pub fn endpoint(&self) -> &str {
+        self.endpoint
+            .as_deref()
+            .unwrap_or_else(|| self.protocol.default_endpoint())
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            protocol: Protocol::default(),
+            endpoint: None,
+            use_batch: true,
+            batch_config: BatchConfig::default(),
+            headers: HashMap::new(),
+            resource_attributes: Vec::new(),
+            sampler: Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+            shutdown_timeout: guard::DEFAULT_SHUTDOWN_TIMEOUT,
+            backtrace_policy: None,
+            otlp_exporter: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TelemetryConfigBuilder {
+    service_name: Option<String>,
+    protocol: Option<Protocol>,
+    endpoint: Option<String>,
+    use_batch: Option<bool>,
+    batch_config: Option<BatchConfig>,
+    headers: HashMap<String, String>,
+    resource_attributes: Vec<KeyValue>,
+    sampler: Option<Sampler>,
+    shutdown_timeout: Option<Duration>,
+    backtrace_policy: Option<BacktracePolicy>,
+    otlp_exporter: Option<SpanExporterBuilder>,
+}
+
+impl TelemetryConfigBuilder {
+    pub fn service_name<S: Into<String>>(mut self, service_name: S) -> Self {
+        self.service_name = Some(service_name.into());
+        self
+    }
+
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    pub fn endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn batch(mut self, use_batch: bool) -> Self {
+        self.use_batch = Some(use_batch);
+        self
+    }
+
+    pub fn batch_config(mut self, batch_config: BatchConfig) -> Self {
+        self.batch_config = Some(batch_config);
+        self
+    }
+
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn resource_attribute(mut self, attribute: KeyValue) -> Self {
+        self.resource_attributes.push(attribute);
+        self
+    }
+
+    pub fn sampler(mut self, sampler: Sampler) -> Self {
+        self.sampler = Some(sampler);
+        self
+    }
+
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = Some(timeout);
+        self
+    }
+
+    pub fn backtrace_policy(mut self, policy: BacktracePolicy) -> Self {
+        self.backtrace_policy = Some(policy);
+        self
+    }
+
+    pub fn otlp_exporter<B: Into<SpanExporterBuilder>>(mut self, exporter: B) -> Self {
+        self.otlp_exporter = Some(exporter.into());
+        self
+    }
+
+    pub fn build(self) -> Result<TelemetryConfig, TrasyError<io::Error>> {
+        let defaults = TelemetryConfig::default();
+
+        let service_name = self.service_name.unwrap_or(defaults.service_name);
+        if service_name.trim().is_empty() {
+            return Err(invalid_input("service_name must not be empty".to_string()));
+        }
+
+        if let Some(endpoint) = &self.endpoint {
+            validate_endpoint(endpoint)?;
+        }
+
+        Ok(TelemetryConfig {
+            service_name,
+            protocol: self.protocol.unwrap_or(defaults.protocol),
+            endpoint: self.endpoint,
+            use_batch: self.use_batch.unwrap_or(defaults.use_batch),
+            batch_config: self.batch_config.unwrap_or(defaults.batch_config),
+            headers: self.headers,
+            resource_attributes: self.resource_attributes,
+            sampler: self.sampler.unwrap_or(defaults.sampler),
+            shutdown_timeout: self.shutdown_timeout.unwrap_or(defaults.shutdown_timeout),
+            backtrace_policy: self.backtrace_policy,
+            otlp_exporter: self.otlp_exporter,
+        })
+    }
+}
+
+fn validate_endpoint(endpoint: &str) -> Result<(), TrasyError<io::Error>> {
+    let host = endpoint
+        .strip_prefix("http://")
+        .or_else(|| endpoint.strip_prefix("https://"))
+        .ok_or_else(|| {
+            invalid_input(format!(
+                "endpoint `{}` must start with http:// or https://",
+                endpoint
+            ))
+        })?;
+
+    if host.is_empty() || host.starts_with('/') || host.starts_with(':') {
+        return Err(invalid_input(format!(
+            "endpoint `{}` has no host",
+            endpoint
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "otlp-grpc")]
+fn grpc_metadata(
+    headers: HashMap<String, String>,
+) -> Result<tonic::metadata::MetadataMap, TrasyError<io::Error>> {
+    use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+
+    let mut metadata = MetadataMap::with_capacity(headers.len());
+    for (key, value) in headers {
+        let name = MetadataKey::from_bytes(key.to_lowercase().as_bytes())
+            .map_err(|_| invalid_input(format!("invalid gRPC metadata key `{}`", key)))?;
+        let value = MetadataValue::try_from(value.as_str())
+            .map_err(|_| invalid_input(format!("invalid gRPC metadata value for `{}`", key)))?;
+        metadata.insert(name, value);
+    }
+    Ok(metadata)
+}
+
+fn invalid_input(message: String) -> TrasyError<io::Error> {
+    TrasyError::new(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+
+pub async fn setup_opentelemetry(
+    config: TelemetryConfig,
+) -> Result<(OpenTelemetryLayer<Registry, Tracer>, TelemetryGuard), TrasyError<io::Error>> {
+    if let Some(policy) = config.backtrace_policy {
+        set_backtrace_policy(policy);
+    }
+
+    let endpoint = config.endpoint().to_string();
+
+    let mut resource = vec![KeyValue::new("service.name", config.service_name)];
+    resource.extend(config.resource_attributes);
+    let pipeline = Pipeline {
+        use_batch: config.use_batch,
+        batch_config: config.batch_config,
+        trace_config: trace::config()
+            .with_sampler(config.sampler)
+            .with_resource(Resource::new(resource)),
+        shutdown_timeout: config.shutdown_timeout,
+    };
+
+    let exporter = match config.otlp_exporter {
+        Some(exporter) => exporter,
+        None => match config.protocol {
+            Protocol::HttpBinary => opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint)
+                .with_headers(config.headers)
+                .into(),
+            #[cfg(feature = "otlp-http-json")]
+            Protocol::HttpJson => {
+                let exporter = json::JsonSpanExporter::new(&endpoint, config.headers)
+                    .map_err(|e| TrasyError::new(io::Error::other(e)))?;
+                return Ok(pipeline.install(exporter));
+            }
+            #[cfg(feature = "otlp-grpc")]
+            Protocol::Grpc => opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint)
+                .with_metadata(grpc_metadata(config.headers)?)
+                .into(),
+        },
+    };
+    let exporter = exporter
+        .build_span_exporter()
+        .map_err(|e| TrasyError::new(io::Error::other(e)))?;
+
+    Ok(pipeline.install(exporter))
+}
+
+// What `opentelemetry-otlp`'s pipeline does in `install_batch` and
+// `install_simple`, for exporters that do not come from that crate.
+struct Pipeline {
+    use_batch: bool,
+    batch_config: BatchConfig,
+    trace_config: trace::Config,
+    shutdown_timeout: Duration,
+}
+
+impl Pipeline {
+    fn install<E: SpanExporter + 'static>(
+        self,
+        exporter: E,
+    ) -> (OpenTelemetryLayer<Registry, Tracer>, TelemetryGuard) {
+        let builder = TracerProvider::builder().with_config(self.trace_config);
+        let provider = if self.use_batch {
+            let processor =
+                BatchSpanProcessor::builder(exporter, opentelemetry_sdk::runtime::Tokio)
+                    .with_batch_config(self.batch_config)
+                    .build();
+            builder.with_span_processor(processor).build()
+        } else {
+            builder.with_simple_exporter(exporter).build()
+        };
+        let tracer = provider.versioned_tracer(
+            env!("CARGO_PKG_NAME"),
+            Some(env!("CARGO_PKG_VERSION")),
+            None::<&str>,
+            None,
+        );
+        let _ = opentelemetry::global::set_tracer_provider(provider.clone());
+
+        let guard = TelemetryGuard::new(Some(provider), self.shutdown_timeout);
+        (tracing_opentelemetry::layer().with_tracer(tracer), guard)
+    }
+}
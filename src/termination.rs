@@ -0,0 +1,172 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::process::{ExitCode, Termination};
+use std::sync::RwLock;
+
+use crate::{Error, ErrorKind, ErrorReport, TelemetryGuard, TrasyError};
+
+const DEFAULT_EXIT_CODE: u8 = 1;
+
+type ExitCodeMapper = fn(&(dyn StdError + 'static)) -> Option<u8>;
+
+static EXIT_CODE_MAPPER: RwLock<Option<ExitCodeMapper>> = RwLock::new(None);
+
+// Maps the inner error of a `Report` to a process exit code. Errors the mapper
+// returns `None` for exit with the code of their `TrasyErrorKind`, or 1.
+pub fn set_exit_code_mapper(mapper: ExitCodeMapper) {
+    *EXIT_CODE_MAPPER.write().unwrap_or_else(|e| e.into_inner()) = Some(mapper);
+}
+
+trait Reportable {
+    fn report(&self) -> ErrorReport<'_>;
+
+    fn inner(&self) -> &(dyn StdError + 'static);
+
+    fn kind(&self) -> Option<&ErrorKind>;
+
+    fn record(&self);
+}
+
+impl<T: StdError + 'static> Reportable for TrasyError<T> {
+    fn report(&self) -> ErrorReport<'_> {
+        TrasyError::<T>::report(self)
+    }
+
+    fn record(&self) {
+        TrasyError::<T>::record_at_boundary(self)
+    }
+
+    fn inner(&self) -> &(dyn StdError + 'static) {
+        &self.inner
+    }
+
+    fn kind(&self) -> Option<&ErrorKind> {
+        TrasyError::<T>::kind(self)
+    }
+}
+
+impl Reportable for Error {
+    fn report(&self) -> ErrorReport<'_> {
+        Error::report(self)
+    }
+
+    fn record(&self) {
+        Error::record_at_boundary(self)
+    }
+
+    fn inner(&self) -> &(dyn StdError + 'static) {
+        &*self.inner
+    }
+
+    fn kind(&self) -> Option<&ErrorKind> {
+        Error::kind(self)
+    }
+}
+
+// Outcome of `main`. When it holds an error, the pretty report is printed to
+// stderr and the process exits with the mapped exit code. `with_guard` flushes
+// telemetry before `main` returns.
+//
+//     fn main() -> trasy::Report {
+//         run().into()
+//     }
+pub struct Report {
+    error: Option<Box<dyn Reportable>>,
+    exit_code: Option<u8>,
+}
+
+impl Report {
+    pub fn success() -> Self {
+        Self {
+            error: None,
+            exit_code: None,
+        }
+    }
+
+    // Overrides the exit code from the mapper and from the error kind.
+    pub fn with_exit_code(mut self, exit_code: u8) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+
+    // Records the error and exports the remaining spans through `guard`. Call
+    // it before returning from `main`: with `#[tokio::main]` the runtime the
+    // exporter runs on is gone by the time the report is printed.
+    pub async fn with_guard(self, guard: TelemetryGuard) -> Self {
+        if let Some(error) = &self.error {
+            error.record();
+        }
+        if let Err(error) = guard.shutdown().await {
+            tracing::warn!("failed to shut down telemetry: {}", error.inner);
+        }
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        let Some(error) = &self.error else {
+            return 0;
+        };
+        if let Some(exit_code) = self.exit_code {
+            return exit_code;
+        }
+
+        let mapper = *EXIT_CODE_MAPPER.read().unwrap_or_else(|e| e.into_inner());
+        mapper
+            .and_then(|mapper| mapper(error.inner()))
+            .or_else(|| error.kind().and_then(|kind| kind.exit_code))
+            .unwrap_or(DEFAULT_EXIT_CODE)
+    }
+
+    fn from_error<E: Reportable + 'static>(error: E) -> Self {
+        Self {
+            error: Some(Box::new(error)),
+            exit_code: None,
+        }
+    }
+}
+
+impl<T: StdError + 'static> From<TrasyError<T>> for Report {
+    fn from(error: TrasyError<T>) -> Self {
+        Report::from_error(error)
+    }
+}
+
+impl From<Error> for Report {
+    fn from(error: Error) -> Self {
+        Report::from_error(error)
+    }
+}
+
+impl<E: Into<Report>> From<Result<(), E>> for Report {
+    fn from(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => Report::success(),
+            Err(error) => error.into(),
+        }
+    }
+}
+
+impl Termination for Report {
+    fn report(self) -> ExitCode {
+        let exit_code = self.exit_code();
+        if let Some(error) = &self.error {
+            eprint!("{}", error.report());
+        }
+        ExitCode::from(exit_code)
+    }
+}
+
+// `fn main() -> Result<(), Report>` prints `Error: {:?}`, so `Debug` renders
+// the report without its own label. That path always exits with code 1.
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            Some(error) => write!(f, "{}", error.report().without_label()),
+            None => f.write_str("Success"),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "axum")]
+
+mod common;
+
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use common::ApiError;
+use serde_json::Value;
+use trasy::{ResultExt, TrasyError};
+
+async fn problem(response: Response) -> (StatusCode, String, Value) {
+    let status = response.status();
+    let content_type = response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, content_type, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn responds_with_a_problem_document() {
+    let response = TrasyError::new(ApiError::NotFound).into_response();
+
+    let (status, content_type, body) = problem(response).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(content_type, "application/problem+json");
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "user not found",
+        })
+    );
+}
+
+#[tokio::test]
+async fn internal_messages_are_not_sent() {
+    let response = TrasyError::new(ApiError::Pool).into_response();
+
+    let (status, _, body) = problem(response).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(body.get("detail").is_none());
+    assert!(!body.to_string().contains("pool"));
+}
+
+#[tokio::test]
+async fn context_keeps_the_status_of_the_wrapped_error() {
+    let result: Result<(), ApiError> = Err(ApiError::NotFound);
+    let response = result
+        .context("loading the profile")
+        .unwrap_err()
+        .into_response();
+
+    let (status, _, body) = problem(response).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["detail"], "user not found");
+    assert!(!body.to_string().contains("profile"));
+}
+
+#[tokio::test]
+async fn dynamic_errors_respond_with_500() {
+    let error: trasy::Error = std::io::Error::other("disk on fire").into();
+
+    let (status, _, body) = problem(error.into_response()).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["title"], "Internal Server Error");
+    assert!(!body.to_string().contains("disk"));
+}
+
+#[tokio::test]
+async fn the_trace_id_is_the_one_of_the_current_span() {
+    let mut response = None;
+    let span = common::export_span(|| {
+        response = Some(TrasyError::new(ApiError::NotFound).into_response());
+    });
+
+    let (_, _, body) = problem(response.unwrap()).await;
+    assert_eq!(body["trace_id"], span.span_context.trace_id().to_string());
+}
@@ -0,0 +1,64 @@
+use thiserror::Error;
+use trasy::{set_backtrace_policy_for, BacktracePolicy, TrasyError};
+
+#[derive(Error, Debug)]
+#[error("expected")]
+struct Expected;
+
+#[derive(Error, Debug)]
+#[error("unexpected")]
+struct Unexpected;
+
+#[derive(Error, Debug)]
+#[error("rare")]
+struct Rare;
+
+#[derive(Error, Debug)]
+#[error("frequent")]
+struct Frequent;
+
+#[derive(Error, Debug)]
+#[error("occasional")]
+struct Occasional;
+
+// Per-type policies take precedence over the global one, so these tests do
+// not depend on `RUST_BACKTRACE` or on each other.
+#[test]
+fn per_type_policies_override_the_global_policy() {
+    set_backtrace_policy_for::<Expected>(BacktracePolicy::Never);
+    set_backtrace_policy_for::<Unexpected>(BacktracePolicy::Always);
+
+    assert!(TrasyError::new(Expected).backtrace().is_none());
+    assert!(TrasyError::new(Unexpected).backtrace().is_some());
+}
+
+#[test]
+fn per_type_policies_apply_to_borrowed_types() {
+    set_backtrace_policy_for::<&str>(BacktracePolicy::Always);
+
+    let message = String::from("borrowed");
+    assert!(TrasyError::new(message.as_str()).backtrace().is_some());
+}
+
+#[test]
+fn sampled_captures_one_backtrace_in_n() {
+    set_backtrace_policy_for::<Rare>(BacktracePolicy::Sampled(5));
+
+    let captured = (0..20)
+        .filter(|_| TrasyError::new(Rare).backtrace().is_some())
+        .count();
+    assert_eq!(captured, 4);
+}
+
+#[test]
+fn sampled_types_count_their_errors_apart() {
+    set_backtrace_policy_for::<Frequent>(BacktracePolicy::Sampled(2));
+    set_backtrace_policy_for::<Occasional>(BacktracePolicy::Sampled(3));
+
+    let (mut frequent, mut occasional) = (0, 0);
+    for _ in 0..12 {
+        frequent += usize::from(TrasyError::new(Frequent).backtrace().is_some());
+        occasional += usize::from(TrasyError::new(Occasional).backtrace().is_some());
+    }
+    assert_eq!((frequent, occasional), (6, 4));
+}
@@ -0,0 +1,177 @@
+// Shared by the integration tests; each of them uses only a part.
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{Key, KeyValue, Value};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::{Identity, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+
+pub fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such file")
+}
+
+// The messages of `error` and its sources, outermost first.
+pub fn chain(error: &(dyn Error + 'static)) -> Vec<String> {
+    std::iter::successors(Some(error), |&error| error.source())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to load settings")]
+pub struct Settings(#[source] pub io::Error);
+
+// An application error with one public and one internal variant, mapped to a
+// status by the axum and tonic integrations.
+#[derive(thiserror::Error, Debug)]
+pub enum ApiError {
+    #[error("user 42 not found in shard 7")]
+    NotFound,
+
+    #[error("connection pool exhausted")]
+    Pool,
+}
+
+impl ApiError {
+    fn public_message(&self) -> Option<String> {
+        match self {
+            ApiError::NotFound => Some("user not found".to_string()),
+            ApiError::Pool => None,
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl trasy::HttpStatus for ApiError {
+    fn status_code(&self) -> axum::http::StatusCode {
+        match self {
+            ApiError::NotFound => axum::http::StatusCode::NOT_FOUND,
+            ApiError::Pool => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn public_message(&self) -> Option<String> {
+        ApiError::public_message(self)
+    }
+}
+
+#[cfg(feature = "tonic")]
+impl trasy::GrpcStatus for ApiError {
+    fn grpc_code(&self) -> tonic::Code {
+        match self {
+            ApiError::NotFound => tonic::Code::NotFound,
+            ApiError::Pool => tonic::Code::Unavailable,
+        }
+    }
+
+    fn reason(&self) -> Option<String> {
+        match self {
+            ApiError::NotFound => Some("USER_NOT_FOUND".to_string()),
+            ApiError::Pool => None,
+        }
+    }
+
+    fn public_message(&self) -> Option<String> {
+        ApiError::public_message(self)
+    }
+}
+
+pub struct Request {
+    pub path: String,
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+// A fake OTLP/HTTP collector that accepts a single request. Returns its
+// endpoint and where the request arrives.
+pub fn collector() -> (String, mpsc::Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        let mut content_type = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(": ").unwrap();
+            match name.to_lowercase().as_str() {
+                "content-type" => content_type = value.to_string(),
+                "content-length" => content_length = value.parse().unwrap(),
+                _ => {}
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+
+        let path = request_line.split(' ').nth(1).unwrap().to_string();
+        let _ = sender.send(Request {
+            path,
+            content_type,
+            body,
+        });
+    });
+
+    (endpoint, receiver)
+}
+
+// Runs `f` inside a span exported through `OpenTelemetryLayer` and returns
+// what reached the exporter.
+pub fn export_span(f: impl FnOnce()) -> SpanData {
+    export_span_with(Identity::new(), f)
+}
+
+// Like `export_span`, with `layer` added below `OpenTelemetryLayer`.
+pub fn export_span_with<L>(layer: L, f: impl FnOnce()) -> SpanData
+where
+    L: Layer<Registry> + Send + Sync + 'static,
+{
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = Registry::default()
+        .with(layer)
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+        .with(ErrorLayer::default());
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("request").in_scope(f);
+    });
+    provider.force_flush();
+
+    let mut spans = exporter.get_finished_spans().unwrap();
+    assert_eq!(spans.len(), 1);
+    spans.remove(0)
+}
+
+pub fn attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a Value> {
+    let key = Key::from(key.to_string());
+    attributes
+        .iter()
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| &attribute.value)
+}
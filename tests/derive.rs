@@ -0,0 +1,124 @@
+#![cfg(all(feature = "derive", feature = "axum", feature = "tonic"))]
+
+use std::error::Error as _;
+use std::io;
+use std::num::ParseIntError;
+
+use axum::http::StatusCode;
+use thiserror::Error;
+use tonic::Code;
+use trasy::{ErrorKind, GrpcStatus, HttpStatus, Severity, Trasy, TrasyError, TrasyErrorKind};
+
+#[derive(Debug, Error, Trasy)]
+#[trasy(category = "users")]
+enum AppError {
+    #[error("user not found")]
+    #[trasy(code = "E1001", http = 404, grpc = "NotFound", severity = "warning")]
+    NotFound,
+
+    #[error("invalid age")]
+    #[trasy(code = "E1002", http = 400, doc_url = "https://docs.example.com/E1002")]
+    InvalidAge(
+        #[source]
+        #[trasy(from)]
+        ParseIntError,
+    ),
+
+    #[error("storage unavailable")]
+    #[trasy(
+        code = "E1003",
+        category = "storage",
+        grpc = "Unavailable",
+        retryable,
+        exit_code = 74
+    )]
+    Storage {
+        #[source]
+        #[trasy(from)]
+        source: io::Error,
+    },
+}
+
+fn storage() -> AppError {
+    AppError::from(io::Error::other("disk full"))
+}
+
+fn invalid_age() -> AppError {
+    AppError::from("x".parse::<u8>().unwrap_err())
+}
+
+#[test]
+fn kind_attributes() {
+    assert_eq!(AppError::NotFound.code(), "E1001");
+    assert_eq!(AppError::NotFound.category(), "users");
+    assert_eq!(AppError::NotFound.severity(), Severity::Warning);
+    assert!(!AppError::NotFound.is_retryable());
+    assert_eq!(AppError::NotFound.exit_code(), None);
+
+    assert_eq!(
+        invalid_age().doc_url(),
+        Some("https://docs.example.com/E1002")
+    );
+
+    assert_eq!(storage().code(), "E1003");
+    assert_eq!(storage().category(), "storage");
+    assert_eq!(storage().severity(), Severity::Error);
+    assert!(storage().is_retryable());
+    assert_eq!(storage().exit_code(), Some(74));
+
+    assert_eq!(
+        AppError::kinds(),
+        [
+            ErrorKind::new("E1001", "users"),
+            ErrorKind::new("E1002", "users").with_doc_url("https://docs.example.com/E1002"),
+            ErrorKind::new("E1003", "storage").with_exit_code(74),
+        ]
+    );
+}
+
+#[test]
+fn status_attributes() {
+    assert_eq!(AppError::NotFound.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(invalid_age().status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(storage().status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    assert_eq!(AppError::NotFound.grpc_code(), Code::NotFound);
+    assert_eq!(invalid_age().grpc_code(), Code::Internal);
+    assert_eq!(storage().grpc_code(), Code::Unavailable);
+}
+
+#[test]
+fn from_conversions() {
+    assert!(matches!(invalid_age(), AppError::InvalidAge(_)));
+    assert!(matches!(storage(), AppError::Storage { .. }));
+
+    let error: TrasyError<AppError> = AppError::NotFound.into();
+    assert_eq!(error.kind().unwrap().code, "E1001");
+}
+
+fn parse_age(text: &str) -> Result<u8, AppError> {
+    Ok(text.parse::<u8>()?)
+}
+
+fn load(path: &str, age: &str) -> Result<u8, TrasyError<AppError>> {
+    std::fs::read(path).map_err(AppError::from)?;
+    Ok(parse_age(age)?)
+}
+
+fn inner(error: &TrasyError<AppError>) -> &AppError {
+    error.source().unwrap().downcast_ref::<AppError>().unwrap()
+}
+
+#[test]
+fn question_mark_end_to_end() {
+    let error = load("/definitely/not/here", "42").unwrap_err();
+    assert!(matches!(inner(&error), AppError::Storage { .. }));
+    assert_eq!(error.kind().unwrap().code, "E1003");
+
+    let error = load(file!(), "old").unwrap_err();
+    assert!(matches!(inner(&error), AppError::InvalidAge(_)));
+    assert_eq!(error.kind().unwrap().code, "E1002");
+    assert_eq!(error.location().file(), file!());
+
+    assert_eq!(load(file!(), "42").unwrap(), 42);
+}
@@ -0,0 +1,8 @@
+#![cfg(all(feature = "derive", feature = "axum", feature = "tonic"))]
+
+#[test]
+fn derive_ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
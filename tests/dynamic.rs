@@ -0,0 +1,77 @@
+mod common;
+
+use std::error::Error;
+use std::io;
+use std::num::ParseIntError;
+
+use common::not_found;
+use trasy::TrasyError;
+
+fn parse(text: &str) -> trasy::Result<u16> {
+    Ok(text.parse::<u16>()?)
+}
+
+#[test]
+fn is_checks_the_inner_type() {
+    let error = parse("port").unwrap_err();
+    assert!(error.is::<ParseIntError>());
+    assert!(!error.is::<io::Error>());
+}
+
+#[test]
+fn downcast_ref_and_mut_reach_the_inner_error() {
+    let mut error = trasy::Error::from(not_found());
+    assert_eq!(
+        error.downcast_ref::<io::Error>().unwrap().kind(),
+        io::ErrorKind::NotFound
+    );
+    assert!(error.downcast_ref::<ParseIntError>().is_none());
+
+    *error.downcast_mut::<io::Error>().unwrap() = io::Error::other("replaced");
+    assert_eq!(
+        error.downcast_ref::<io::Error>().unwrap().to_string(),
+        "replaced"
+    );
+}
+
+#[test]
+fn downcast_keeps_the_context() {
+    let error = trasy::Error::from(not_found());
+    let location = error.location();
+
+    let error = error.downcast::<ParseIntError>().unwrap_err();
+    let typed: TrasyError<io::Error> = error.downcast().unwrap();
+    assert_eq!(typed.location(), location);
+    let source = typed.source().unwrap().downcast_ref::<io::Error>();
+    assert_eq!(source.unwrap().kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn map_err_boxed_keeps_the_context_of_a_trasy_error() {
+    fn open() -> Result<(), TrasyError<io::Error>> {
+        Err(TrasyError::new(not_found()).with_field("path", "/etc/app.toml"))
+    }
+    fn load() -> trasy::Result<()> {
+        open().map_err(TrasyError::boxed)?;
+        Ok(())
+    }
+
+    let typed = open().unwrap_err();
+    let error = load().unwrap_err();
+    assert_eq!(error.location().line(), typed.location().line());
+    assert_eq!(error.fields().len(), 1);
+    assert!(error.is::<io::Error>());
+    assert!(error.to_string().starts_with("Error: no such file\n"));
+}
+
+#[test]
+fn question_mark_wraps_a_trasy_error() {
+    fn load() -> trasy::Result<()> {
+        Err(TrasyError::new(not_found()))?;
+        Ok(())
+    }
+
+    let error = load().unwrap_err();
+    assert!(error.is::<TrasyError<io::Error>>());
+    assert!(!error.is::<io::Error>());
+}
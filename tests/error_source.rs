@@ -0,0 +1,66 @@
+mod common;
+
+use std::error::Error;
+use std::io;
+
+use common::chain;
+use thiserror::Error;
+use trasy::TrasyError;
+
+#[derive(Error, Debug)]
+enum AppError {
+    #[error("failed to load settings")]
+    Settings(#[source] io::Error),
+
+    #[error("invalid port")]
+    Port(#[from] std::num::ParseIntError),
+}
+
+#[test]
+fn io_error_is_the_source() {
+    let error = TrasyError::new(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+
+    let source = error.source().expect("source should be the inner error");
+    let io_error = source.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(io_error.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn parse_error_is_the_source() {
+    let inner = "abc".parse::<u16>().unwrap_err();
+    let error = TrasyError::new(inner.clone());
+
+    let source = error.source().unwrap();
+    assert_eq!(
+        source.downcast_ref::<std::num::ParseIntError>(),
+        Some(&inner)
+    );
+}
+
+#[test]
+fn thiserror_chain_can_be_walked() {
+    let io_error = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+    let error = TrasyError::new(AppError::Settings(io_error));
+
+    let messages = chain(&error);
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1], "failed to load settings");
+    assert_eq!(messages[2], "permission denied");
+}
+
+#[test]
+fn boxes_into_dyn_error() {
+    fn parse(input: &str) -> Result<u16, TrasyError<AppError>> {
+        input
+            .parse::<u16>()
+            .map_err(|e| TrasyError::new(AppError::from(e)))
+    }
+
+    fn run() -> Result<u16, Box<dyn Error + Send + Sync>> {
+        Ok(parse("http")?)
+    }
+
+    let error = run().unwrap_err();
+    let trasy_error = error.downcast_ref::<TrasyError<AppError>>().unwrap();
+    assert_eq!(chain(trasy_error)[1], "invalid port");
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+use std::io;
+
+use trasy::{ContextError, OptionExt, ResultExt};
+
+fn missing_file() -> Result<(), io::Error> {
+    Err(io::Error::new(io::ErrorKind::NotFound, "no such file"))
+}
+
+#[test]
+fn context_wraps_the_error() {
+    let error = missing_file().context("loading settings").unwrap_err();
+
+    let wrapper = error.source().unwrap();
+    let context = wrapper
+        .downcast_ref::<ContextError<&str, io::Error>>()
+        .unwrap();
+    assert_eq!(*context.context(), "loading settings");
+    assert_eq!(context.error().kind(), io::ErrorKind::NotFound);
+    assert_eq!(wrapper.to_string(), "loading settings");
+    assert_eq!(format!("{:#}", wrapper), "loading settings: no such file");
+}
+
+#[test]
+fn with_context_is_lazy() {
+    let mut called = false;
+    let value: Result<u8, io::Error> = Ok(1);
+    let value = value
+        .with_context(|| {
+            called = true;
+            "never"
+        })
+        .unwrap();
+    assert_eq!(value, 1);
+    assert!(!called);
+
+    let error = missing_file()
+        .with_context(|| format!("loading {}", "settings.toml"))
+        .unwrap_err();
+    assert_eq!(error.source().unwrap().to_string(), "loading settings.toml");
+}
+
+#[test]
+fn source_is_the_original_error() {
+    let error = missing_file().context("loading settings").unwrap_err();
+
+    let original = error.source().unwrap().source().unwrap();
+    let io_error = original.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(io_error.kind(), io::ErrorKind::NotFound);
+    assert!(original.source().is_none());
+}
+
+#[test]
+fn option_context_on_none_is_an_error() {
+    let error = None::<u8>.context("missing port").unwrap_err();
+    assert!(error.to_string().starts_with("Error: missing port\n"));
+
+    let error = None::<u8>
+        .with_context(|| format!("missing {}", "host"))
+        .unwrap_err();
+    assert!(error.to_string().starts_with("Error: missing host\n"));
+}
+
+#[test]
+fn option_context_on_some_is_the_value() {
+    assert_eq!(Some(8080).context("missing port").unwrap(), 8080);
+    assert_eq!(Some(8080).with_context(|| "missing port").unwrap(), 8080);
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "eyre")]
+
+mod common;
+
+use std::error::Error as _;
+use std::io;
+
+use common::{chain, not_found, Settings};
+use eyre::WrapErr;
+use trasy::{BoxedError, EyreError, TrasyError};
+
+#[test]
+fn eyre_context_becomes_the_source_chain() {
+    let load = || -> Result<(), TrasyError<EyreError>> {
+        Err(not_found()).wrap_err("reading config.toml")?;
+        Ok(())
+    };
+
+    let error = load().unwrap_err();
+    assert_eq!(
+        chain(error.source().unwrap()),
+        ["reading config.toml", "no such file"]
+    );
+
+    let error = error.boxed();
+    let inner = error.downcast_ref::<EyreError>().unwrap();
+    let io_error = inner.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(io_error.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn dynamic_error_keeps_its_chain_in_eyre() {
+    let error: trasy::Error = TrasyError::new(Settings(not_found())).boxed();
+    let error: eyre::Report = error.into();
+
+    let trasy_error = error.downcast_ref::<TrasyError<BoxedError>>().unwrap();
+    assert_eq!(trasy_error.location().file(), file!());
+    let inner = trasy_error.source().unwrap();
+    let boxed = inner.downcast_ref::<BoxedError>().unwrap();
+    assert!(boxed.downcast_ref::<Settings>().is_some());
+
+    let causes: Vec<String> = error.chain().skip(1).map(ToString::to_string).collect();
+    assert_eq!(causes, ["failed to load settings", "no such file"]);
+    assert!(error.root_cause().downcast_ref::<io::Error>().is_some());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use tracing_subscriber::layer::SubscriberExt;
+use trasy::{setup_opentelemetry, TelemetryConfig, TelemetryGuard};
+
+async fn setup(endpoint: String) -> TelemetryGuard {
+    let config = TelemetryConfig::builder()
+        .service_name("guard-test")
+        .endpoint(endpoint)
+        .shutdown_timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let (layer, guard) = setup_opentelemetry(config).await.unwrap();
+
+    // The batch processor keeps the span until it is flushed.
+    let subscriber = tracing_subscriber::Registry::default().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("job").in_scope(|| {});
+    });
+    guard
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn shutdown_exports_the_last_batch_on_a_current_thread_runtime() {
+    let (endpoint, requests) = common::collector();
+    let guard = setup(endpoint).await;
+
+    guard.shutdown().await.unwrap();
+
+    let request = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(request.path, "/v1/traces");
+    assert_eq!(request.content_type, "application/x-protobuf");
+    assert!(!request.body.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn drop_exports_the_last_batch_on_a_multi_thread_runtime() {
+    let (endpoint, requests) = common::collector();
+    let guard = setup(endpoint).await;
+
+    drop(guard);
+
+    let request = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(request.path, "/v1/traces");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn drop_does_not_block_a_current_thread_runtime() {
+    let (endpoint, _requests) = common::collector();
+    let guard = setup(endpoint).await;
+
+    let started = Instant::now();
+    drop(guard);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn report_with_guard_exports_the_last_batch() {
+    let (endpoint, requests) = common::collector();
+    let guard = setup(endpoint).await;
+
+    let result: trasy::Result<()> = trasy::bail!("job failed");
+    let report = trasy::Report::from(result).with_guard(guard).await;
+    assert_eq!(report.exit_code(), 1);
+
+    let request = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(request.path, "/v1/traces");
+    assert!(!request.body.is_empty());
+}
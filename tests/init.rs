@@ -0,0 +1,45 @@
+use std::io;
+
+use trasy::{error, Trasy};
+
+#[tokio::test]
+async fn install_sets_the_global_subscriber_once() {
+    let guard = Trasy::builder()
+        .opentelemetry(false)
+        .env_filter(false)
+        .fmt(false)
+        .install()
+        .await
+        .unwrap();
+
+    // The `ErrorLayer` is part of the installed stack.
+    let error = tracing::info_span!("request").in_scope(|| error!("failed"));
+    assert!(error.has_span_context());
+
+    let second = Trasy::builder()
+        .opentelemetry(false)
+        .env_filter(false)
+        .fmt(false)
+        .install()
+        .await;
+    assert!(second.is_err());
+
+    guard.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn install_rejects_invalid_directives() {
+    std::env::remove_var("RUST_LOG");
+    let result = Trasy::builder()
+        .opentelemetry(false)
+        .default_directive("info,[")
+        .install()
+        .await;
+    let Err(error) = result else {
+        panic!("invalid directives were accepted");
+    };
+
+    let source = std::error::Error::source(&error).unwrap();
+    let source = source.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "serde")]
+
+use std::io;
+
+use serde_json::Value;
+use thiserror::Error;
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use trasy::{set_backtrace_policy_for, BacktracePolicy, ErrorKind, TrasyError, TrasyErrorKind};
+
+#[derive(Error, Debug)]
+enum StorageError {
+    #[error("failed to write the snapshot")]
+    Write(#[source] io::Error),
+}
+
+impl TrasyErrorKind for StorageError {
+    fn code(&self) -> &'static str {
+        "E2001"
+    }
+
+    fn category(&self) -> &'static str {
+        "storage"
+    }
+
+    fn doc_url(&self) -> Option<&'static str> {
+        Some("https://example.com/errors/E2001")
+    }
+
+    fn kinds() -> Vec<ErrorKind> {
+        vec![ErrorKind::new("E2001", "storage")]
+    }
+}
+
+fn validate(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schema/trasy-error.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{} at {}", error, error.instance_path))
+        .collect();
+    assert!(errors.is_empty(), "{:#?}\n{:#}", errors, instance);
+}
+
+#[test]
+fn serialized_error_matches_the_schema() {
+    set_backtrace_policy_for::<StorageError>(BacktracePolicy::Always);
+    let subscriber = tracing_subscriber::Registry::default().with(ErrorLayer::default());
+    let error = tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("snapshot", shard = 4).in_scope(|| {
+            let full = io::Error::new(io::ErrorKind::StorageFull, "disk full");
+            TrasyError::new_with_kind(StorageError::Write(full))
+                .with_field("path", "/var/lib/app/snapshot")
+                .with_field("bytes", 4096)
+                .with_field("compressed", true)
+                .with_field("ratio", 0.5)
+        })
+    });
+
+    let json = serde_json::to_value(&error).unwrap();
+    validate(&json);
+
+    assert_eq!(json["message"], "failed to write the snapshot");
+    assert_eq!(json["kind"]["code"], "E2001");
+    assert_eq!(json["kind"]["category"], "storage");
+    assert_eq!(json["location"]["file"], file!());
+    assert_eq!(json["causes"][0], "disk full");
+    assert_eq!(json["span_trace"][0]["name"], "snapshot");
+    assert_eq!(json["span_trace"][0]["fields"]["shard"], "4");
+    assert!(!json["backtrace"].as_array().unwrap().is_empty());
+    assert_eq!(json["fields"]["path"], "/var/lib/app/snapshot");
+    assert_eq!(json["fields"]["bytes"], 4096);
+    assert_eq!(json["fields"]["compressed"], true);
+}
+
+#[test]
+fn dynamic_error_without_kind_matches_the_schema() {
+    let error: trasy::Error = io::Error::new(io::ErrorKind::NotFound, "no such file").into();
+
+    let json = serde_json::to_value(&error).unwrap();
+    validate(&json);
+
+    assert_eq!(json["kind"], Value::Null);
+    assert_eq!(json["span_trace"], serde_json::json!([]));
+}
+
+#[test]
+fn schema_rejects_unknown_properties() {
+    let error = TrasyError::new(io::Error::other("boom"));
+    let mut json = serde_json::to_value(&error).unwrap();
+    json["extra"] = Value::Bool(true);
+
+    let schema: Value =
+        serde_json::from_str(include_str!("../schema/trasy-error.schema.json")).unwrap();
+    assert!(!jsonschema::is_valid(&schema, &json));
+}
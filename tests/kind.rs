@@ -0,0 +1,155 @@
+use thiserror::Error;
+use trasy::{ErrorKind, ResultExt, Severity, TrasyError, TrasyErrorKind};
+
+#[derive(Error, Debug)]
+enum DbError {
+    #[error("query timed out")]
+    Timeout,
+
+    #[error("connection refused")]
+    Refused,
+}
+
+impl TrasyErrorKind for DbError {
+    fn code(&self) -> &'static str {
+        match self {
+            DbError::Timeout => "db.timeout",
+            DbError::Refused => "E1042",
+        }
+    }
+
+    fn category(&self) -> &'static str {
+        "database"
+    }
+
+    fn doc_url(&self) -> Option<&'static str> {
+        match self {
+            DbError::Timeout => Some("https://docs.example.com/errors/db.timeout"),
+            DbError::Refused => None,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        matches!(self, DbError::Timeout)
+    }
+
+    fn kinds() -> Vec<ErrorKind> {
+        vec![
+            ErrorKind::of(&DbError::Timeout),
+            ErrorKind::of(&DbError::Refused),
+        ]
+    }
+}
+
+// Relies on the default `kinds()`.
+#[derive(Error, Debug)]
+#[error("cache miss")]
+struct CacheMiss;
+
+impl TrasyErrorKind for CacheMiss {
+    fn code(&self) -> &'static str {
+        "cache.miss"
+    }
+
+    fn category(&self) -> &'static str {
+        "cache"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+}
+
+// Never registered.
+#[derive(Error, Debug)]
+#[error("quota exceeded")]
+struct Quota;
+
+impl TrasyErrorKind for Quota {
+    fn code(&self) -> &'static str {
+        "quota"
+    }
+
+    fn category(&self) -> &'static str {
+        "limits"
+    }
+}
+
+fn register() {
+    trasy::register_error_kinds::<DbError>();
+    trasy::register_error_kinds::<CacheMiss>();
+}
+
+#[test]
+fn kind_of_an_error() {
+    let kind = ErrorKind::of(&DbError::Timeout);
+    assert_eq!(kind.code, "db.timeout");
+    assert_eq!(kind.category, "database");
+    assert_eq!(
+        kind.doc_url,
+        Some("https://docs.example.com/errors/db.timeout")
+    );
+    assert!(DbError::Timeout.is_retryable());
+    assert_eq!(CacheMiss.severity(), Severity::Info);
+
+    let error = TrasyError::new_with_kind(DbError::Refused);
+    assert_eq!(error.kind().map(|kind| kind.code), Some("E1042"));
+    assert!(error.to_string().contains("E1042"));
+}
+
+#[test]
+fn registered_kinds_are_listed_by_code() {
+    register();
+    register();
+
+    let codes: Vec<&str> = trasy::error_kinds().iter().map(|kind| kind.code).collect();
+    assert_eq!(codes, ["E1042", "db.timeout"]);
+}
+
+#[test]
+fn question_mark_records_the_kind_of_registered_types() {
+    register();
+    let query = || -> trasy::Result<()> {
+        Err(DbError::Timeout)?;
+        Ok(())
+    };
+    let lookup = || -> trasy::Result<()> {
+        Err(CacheMiss)?;
+        Ok(())
+    };
+    let spend = || -> trasy::Result<()> {
+        Err(Quota)?;
+        Ok(())
+    };
+
+    assert_eq!(query().unwrap_err().kind().unwrap().code, "db.timeout");
+    assert_eq!(lookup().unwrap_err().kind().unwrap().code, "cache.miss");
+    assert!(spend().unwrap_err().kind().is_none());
+}
+
+#[test]
+fn boxed_keeps_or_resolves_the_kind_of_a_trasy_error() {
+    register();
+    let kept = TrasyError::new_with_kind(Quota).boxed();
+    let resolved = TrasyError::new(DbError::Refused).boxed();
+    let unregistered = TrasyError::new(Quota).boxed();
+
+    assert_eq!(kept.kind().unwrap().code, "quota");
+    assert_eq!(resolved.kind().unwrap().code, "E1042");
+    assert!(unregistered.kind().is_none());
+}
+
+#[test]
+fn new_and_context_record_the_kind_with_with_kind() {
+    let error = TrasyError::new(Quota);
+    assert!(error.kind().is_none());
+    assert_eq!(error.with_kind().kind().unwrap().code, "quota");
+
+    let result: Result<(), Quota> = Err(Quota);
+    let error = result
+        .context("charging the account")
+        .map_err(TrasyError::with_kind)
+        .unwrap_err();
+    assert_eq!(error.kind().unwrap().code, "quota");
+    assert_eq!(error.kind().unwrap().category, "limits");
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::io;
+use std::panic::Location;
+
+use common::not_found;
+use trasy::{error, OptionExt, ResultExt, TrasyError};
+
+fn assert_location(location: &Location<'_>, line: u32) {
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+}
+
+#[test]
+fn error_macro_records_the_call_site() {
+    let line = line!() + 1;
+    let error = error!("connection refused");
+    assert_location(error.location(), line);
+
+    let line = line!() + 1;
+    let error = error!(not_found(), path = "/etc/app.toml");
+    assert_location(error.location(), line);
+}
+
+#[test]
+fn new_records_the_call_site() {
+    let line = line!() + 1;
+    let error = TrasyError::new(not_found());
+    assert_location(error.location(), line);
+}
+
+#[test]
+fn question_mark_records_the_conversion_site() {
+    let mut line = 0;
+    let mut read = || -> trasy::Result<()> {
+        line = line!() + 1;
+        Err(not_found())?;
+        Ok(())
+    };
+
+    let error = read().unwrap_err();
+    assert_location(error.location(), line);
+}
+
+#[test]
+fn boxed_keeps_the_location_of_a_trasy_error() {
+    let line = line!() + 1;
+    let inner = TrasyError::new(not_found());
+    let read = move || -> trasy::Result<()> {
+        Err(inner.boxed())?;
+        Ok(())
+    };
+
+    let error = read().unwrap_err();
+    assert_location(error.location(), line);
+}
+
+#[test]
+fn context_records_the_call_site() {
+    let result: Result<(), io::Error> = Err(not_found());
+    let line = line!() + 1;
+    let error = result.context("loading settings").unwrap_err();
+    assert_location(error.location(), line);
+
+    let result: Result<(), io::Error> = Err(not_found());
+    let line = line!() + 1;
+    let error = result.with_context(|| "loading settings").unwrap_err();
+    assert_location(error.location(), line);
+
+    let line = line!() + 1;
+    let error = None::<()>.context("missing setting").unwrap_err();
+    assert_location(error.location(), line);
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use opentelemetry::Value;
+use trasy::{bail, ensure, error, TrasyError};
+
+#[derive(Debug)]
+struct AppError;
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operation failed")
+    }
+}
+
+fn message<T: fmt::Debug + fmt::Display>(error: &TrasyError<T>) -> String {
+    let display = error.to_string();
+    let line = display.lines().next().unwrap();
+    line.strip_prefix("Error: ").unwrap().to_string()
+}
+
+fn field<T>(error: &TrasyError<T>, key: &str) -> Value {
+    let field = error
+        .fields()
+        .iter()
+        .find(|field| field.key.as_str() == key);
+    field.unwrap().value.clone()
+}
+
+#[test]
+fn error_with_a_literal() {
+    let error = error!("plain message");
+    assert_eq!(message(&error), "plain message");
+    assert!(error.fields().is_empty());
+
+    let name = "config";
+    assert_eq!(message(&error!("missing {name}")), "missing config");
+}
+
+#[test]
+fn error_with_a_literal_and_fields() {
+    let error = error!("user not found", user_id = 42, shard = "eu",);
+    assert_eq!(message(&error), "user not found");
+    assert_eq!(field(&error, "user_id"), Value::I64(42));
+    assert_eq!(field(&error, "shard"), Value::from("eu"));
+}
+
+// Not a named format argument: the message captures the local `id`.
+#[test]
+fn key_value_pairs_after_a_literal_are_fields() {
+    let id = 7;
+    let error = error!("user {id} not found", id = 42);
+    assert_eq!(message(&error), "user 7 not found");
+    assert_eq!(field(&error, "id"), Value::I64(42));
+}
+
+#[test]
+fn error_with_a_format_string() {
+    let error = error!("failed to open {}: {}", "a.txt", "denied");
+    assert_eq!(message(&error), "failed to open a.txt: denied");
+    assert!(error.fields().is_empty());
+}
+
+#[test]
+fn error_with_an_expression() {
+    let error: TrasyError<AppError> = error!(AppError);
+    assert_eq!(message(&error), "operation failed");
+    assert!(error.kind().is_none());
+}
+
+#[test]
+fn error_with_an_expression_and_fields() {
+    let error = error!(AppError, user_id = 42);
+    assert_eq!(message(&error), "operation failed");
+    assert_eq!(field(&error, "user_id"), Value::I64(42));
+}
+
+#[test]
+fn bail_returns_the_error() {
+    fn fails(fields: bool) -> trasy::Result<()> {
+        if fields {
+            return bail!("bad input", attempt = 3);
+        }
+        bail!("bad input {}", 7)
+    }
+
+    let error = fails(true).unwrap_err();
+    assert_eq!(message(&error), "bad input");
+    assert_eq!(field(&error, "attempt"), Value::I64(3));
+    assert_eq!(message(&fails(false).unwrap_err()), "bad input 7");
+}
+
+#[test]
+fn ensure_names_the_failed_condition() {
+    fn check(port: u16) -> trasy::Result<u16> {
+        ensure!(port != 0);
+        Ok(port)
+    }
+
+    assert_eq!(check(8080).unwrap(), 8080);
+    let error = check(0).unwrap_err();
+    assert_eq!(message(&error), "Condition failed: `port != 0`");
+}
+
+#[test]
+fn ensure_with_a_format_string() {
+    fn check(port: u16) -> trasy::Result<u16> {
+        ensure!(port >= 1024, "port {} is reserved", port);
+        Ok(port)
+    }
+
+    assert_eq!(check(8080).unwrap(), 8080);
+    assert_eq!(message(&check(80).unwrap_err()), "port 80 is reserved");
+}
+
+#[test]
+fn ensure_with_an_error_expression() {
+    fn check(ready: bool) -> Result<(), TrasyError<AppError>> {
+        ensure!(ready, AppError);
+        Ok(())
+    }
+
+    assert!(check(true).is_ok());
+    assert_eq!(message(&check(false).unwrap_err()), "operation failed");
+}
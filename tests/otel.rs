@@ -0,0 +1,111 @@
+mod common;
+
+use std::io;
+use std::process::Termination;
+
+use opentelemetry::trace::Status;
+use opentelemetry::Value;
+use thiserror::Error;
+use trasy::{ResultExt, TrasyError, TrasyErrorKind};
+
+use common::{attribute, export_span};
+
+#[derive(Error, Debug)]
+#[error("query timed out")]
+struct Timeout;
+
+impl TrasyErrorKind for Timeout {
+    fn code(&self) -> &'static str {
+        "db.timeout"
+    }
+
+    fn category(&self) -> &'static str {
+        "database"
+    }
+}
+
+#[test]
+fn creating_an_error_records_an_exception_event() {
+    let line = line!() + 2;
+    let span = export_span(|| {
+        let _ = trasy::error!("connection refused");
+    });
+
+    assert_eq!(span.status, Status::error("connection refused"));
+    assert_eq!(span.events.len(), 1);
+    let event = span.events.iter().next().unwrap();
+    assert_eq!(event.name, "exception");
+    assert_eq!(
+        attribute(&event.attributes, "exception.message"),
+        Some(&Value::from("connection refused"))
+    );
+    assert_eq!(
+        attribute(&event.attributes, "exception.type"),
+        Some(&Value::from("trasy::dynamic::DynError"))
+    );
+    assert_eq!(
+        attribute(&event.attributes, "code.filepath"),
+        Some(&Value::from(file!()))
+    );
+    assert_eq!(
+        attribute(&event.attributes, "code.lineno"),
+        Some(&Value::I64(i64::from(line)))
+    );
+}
+
+#[test]
+fn fields_become_span_attributes() {
+    let span = export_span(|| {
+        let _ = trasy::error!("user not found", user_id = 42, shard = "eu");
+    });
+
+    assert_eq!(
+        attribute(&span.attributes, "user_id"),
+        Some(&Value::I64(42))
+    );
+    assert_eq!(
+        attribute(&span.attributes, "shard"),
+        Some(&Value::from("eu"))
+    );
+}
+
+#[test]
+fn question_mark_and_context_record_the_error() {
+    fn parse() -> trasy::Result<u16> {
+        Ok("port".parse::<u16>()?)
+    }
+
+    let span = export_span(|| {
+        let _ = parse();
+    });
+    assert_eq!(span.events.len(), 1);
+
+    let span = export_span(|| {
+        let result: Result<(), io::Error> = Err(io::Error::other("disk full"));
+        let _ = result.context("saving settings");
+    });
+    assert_eq!(span.status, Status::error("saving settings"));
+}
+
+#[test]
+fn the_kind_becomes_the_error_type_attribute() {
+    let span = export_span(|| {
+        let _ = TrasyError::new_with_kind(Timeout);
+    });
+
+    assert_eq!(
+        attribute(&span.attributes, "error.type"),
+        Some(&Value::from("db.timeout"))
+    );
+    assert_eq!(span.events.len(), 1);
+}
+
+#[test]
+fn errors_are_recorded_once() {
+    let span = export_span(|| {
+        let error = trasy::error!("connection refused");
+        Termination::report(trasy::Report::from(error));
+    });
+
+    assert_eq!(span.events.len(), 1);
+}
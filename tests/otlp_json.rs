@@ -0,0 +1,41 @@
+#![cfg(feature = "otlp-http-json")]
+
+mod common;
+
+use tracing_subscriber::layer::SubscriberExt;
+use trasy::{setup_opentelemetry, Protocol, TelemetryConfig};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spans_are_exported_as_json() {
+    let (endpoint, requests) = common::collector();
+    let config = TelemetryConfig::builder()
+        .service_name("json-test")
+        .protocol(Protocol::HttpJson)
+        .endpoint(endpoint)
+        .batch(false)
+        .build()
+        .unwrap();
+    let (layer, guard) = setup_opentelemetry(config).await.unwrap();
+    let subscriber = tracing_subscriber::Registry::default().with(layer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("checkout").in_scope(|| {});
+    });
+    guard.shutdown().await.unwrap();
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.path, "/v1/traces");
+    assert_eq!(request.content_type, "application/json");
+
+    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+    let resource_spans = &body["resourceSpans"][0];
+    let span = &resource_spans["scopeSpans"][0]["spans"][0];
+    assert_eq!(span["name"], "checkout");
+    assert_eq!(span["traceId"].as_str().unwrap().len(), 32);
+    assert_eq!(span["spanId"].as_str().unwrap().len(), 16);
+
+    let attributes = resource_spans["resource"]["attributes"].as_array().unwrap();
+    assert!(attributes.iter().any(|attribute| {
+        attribute["key"] == "service.name" && attribute["value"]["stringValue"] == "json-test"
+    }));
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::Duration;
+
+use opentelemetry::Value;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+use trasy::{setup_opentelemetry, TelemetryConfig};
+
+thread_local! {
+    // The hooks run on the panicking thread, so each test sees its own count.
+    static PREVIOUS_HOOK_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        // Stands in for std's default hook, which would print the panic again.
+        panic::set_hook(Box::new(|_| {
+            PREVIOUS_HOOK_CALLS.with(|calls| calls.set(calls.get() + 1));
+        }));
+        trasy::install_panic_hook();
+    });
+}
+
+// Holds the extensions of the current span while it panics. A read lock, so
+// they are not poisoned for the layers that run after the panic.
+struct PanicsWhileBorrowed;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for PanicsWhileBorrowed {
+    fn on_event(&self, _event: &Event<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.lookup_current() {
+            let _extensions = span.extensions();
+            panic!("layer failed");
+        }
+    }
+}
+
+#[test]
+fn panics_are_recorded_and_replace_the_previous_hook() {
+    install();
+
+    let span = common::export_span(|| {
+        let result = panic::catch_unwind(|| panic!("worker crashed"));
+        assert!(result.is_err());
+    });
+
+    assert_eq!(PREVIOUS_HOOK_CALLS.with(Cell::get), 0);
+    assert_eq!(span.events.len(), 1);
+    let event = &span.events[0];
+    assert_eq!(event.name, "exception");
+    assert_eq!(
+        common::attribute(&event.attributes, "exception.type"),
+        Some(&Value::from("panic"))
+    );
+    assert_eq!(
+        common::attribute(&event.attributes, "exception.message"),
+        Some(&Value::from("worker crashed"))
+    );
+}
+
+#[test]
+fn panics_inside_the_subscriber_do_not_deadlock() {
+    install();
+
+    let span = common::export_span_with(PanicsWhileBorrowed, || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| tracing::info!("trigger")));
+        assert!(result.is_err());
+    });
+
+    assert_eq!(PREVIOUS_HOOK_CALLS.with(Cell::get), 0);
+    assert!(span.events.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn panics_flush_the_exporter() {
+    install();
+    let (endpoint, requests) = common::collector();
+    let config = TelemetryConfig::builder()
+        .service_name("panic-test")
+        .endpoint(endpoint)
+        .build()
+        .unwrap();
+    let (layer, _guard) = setup_opentelemetry(config).await.unwrap();
+    // Lets the batch processor take its first, immediate tick. Without the
+    // flush, the span would then wait for the next one, five seconds later.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let subscriber = Registry::default().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("job").in_scope(|| {});
+    });
+    let result = panic::catch_unwind(|| panic!("worker crashed"));
+    assert!(result.is_err());
+
+    let request = requests.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(request.path, "/v1/traces");
+}
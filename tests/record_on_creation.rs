@@ -0,0 +1,27 @@
+mod common;
+
+use opentelemetry::trace::Status;
+use opentelemetry::Value;
+
+use common::{attribute, export_span};
+
+#[test]
+fn opting_out_records_only_on_record() {
+    trasy::set_record_on_creation(false);
+
+    let span = export_span(|| {
+        let _ = trasy::error!("handled right away");
+    });
+    assert!(span.events.is_empty());
+    assert_eq!(span.status, Status::Unset);
+
+    let span = export_span(|| {
+        trasy::error!("connection refused", attempt = 3).record();
+    });
+    assert_eq!(span.status, Status::error("connection refused"));
+    let event = span.events.iter().next().unwrap();
+    assert_eq!(
+        attribute(&event.attributes, "attempt"),
+        Some(&Value::I64(3))
+    );
+}
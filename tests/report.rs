@@ -0,0 +1,56 @@
+use std::io;
+
+use thiserror::Error;
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use trasy::{set_backtrace_policy_for, BacktracePolicy, TrasyError};
+
+#[derive(Error, Debug)]
+enum SettingsError {
+    #[error("failed to load settings")]
+    Load(#[source] io::Error),
+}
+
+fn load_settings() -> (TrasyError<SettingsError>, u32) {
+    set_backtrace_policy_for::<SettingsError>(BacktracePolicy::Never);
+    let subscriber = tracing_subscriber::Registry::default().with(ErrorLayer::default());
+    tracing::subscriber::with_default(subscriber, || {
+        let span_line = line!() + 1;
+        let span = tracing::info_span!("load_settings", path = "/etc/app.toml", retry = 2);
+        let _entered = span.enter();
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+        let error = TrasyError::new(SettingsError::Load(denied)).with_field("attempt", 3);
+        (error, span_line)
+    })
+}
+
+#[test]
+fn report_layout_without_colors() {
+    let (error, span_line) = load_settings();
+
+    let expected = format!(
+        "Error: failed to load settings
+    at {location}
+
+Caused by:
+   0: permission denied
+
+Fields:
+      attempt=3
+
+Span trace:
+   0: report::load_settings with path=\"/etc/app.toml\" retry=2
+        at tests/report.rs:{span_line}
+",
+        location = error.location(),
+    );
+    assert_eq!(error.report().color(false).to_string(), expected);
+}
+
+#[test]
+fn report_highlights_span_field_keys() {
+    let (error, _) = load_settings();
+
+    let report = error.report().color(true).to_string();
+    assert!(report.contains("\x1b[36mpath\x1b[0m=\"/etc/app.toml\" \x1b[36mretry\x1b[0m=2"));
+}
@@ -0,0 +1,63 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tracing::{Event, Level, Subscriber};
+use tracing_error::{ErrorLayer, SpanTraceStatus};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+use trasy::{set_span_context_check, SpanContextCheck};
+
+// Counts warnings, standing in for a `fmt` layer.
+#[derive(Clone, Default)]
+struct Warnings(Arc<AtomicUsize>);
+
+impl Warnings {
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl<S: Subscriber> Layer<S> for Warnings {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() == Level::WARN {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+// Creates an error inside a span, under a subscriber with or without
+// `ErrorLayer`.
+fn capture(warnings: &Warnings, error_layer: bool) -> trasy::Error {
+    let layer = error_layer.then(ErrorLayer::default);
+    let subscriber = Registry::default().with(warnings.clone()).with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("request").in_scope(|| trasy::error!("connection refused"))
+    })
+}
+
+// A single test, since the check and the warning are global.
+#[test]
+fn missing_error_layer_is_reported_according_to_the_check() {
+    let warnings = Warnings::default();
+
+    let error = capture(&warnings, true);
+    assert_eq!(error.span_trace().status(), SpanTraceStatus::CAPTURED);
+    assert_eq!(warnings.count(), 0);
+
+    set_span_context_check(SpanContextCheck::Off);
+    let error = capture(&warnings, false);
+    assert_eq!(error.span_trace().status(), SpanTraceStatus::UNSUPPORTED);
+    assert!(!error.has_span_context());
+    assert_eq!(warnings.count(), 0);
+
+    set_span_context_check(SpanContextCheck::Warn);
+    capture(&warnings, false);
+    capture(&warnings, false);
+    assert_eq!(warnings.count(), 1);
+
+    set_span_context_check(SpanContextCheck::Assert);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| capture(&warnings, false)));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+    assert_eq!(warnings.count(), 1);
+}
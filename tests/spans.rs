@@ -0,0 +1,42 @@
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Registry;
+
+fn capture() -> trasy::Error {
+    let subscriber = Registry::default().with(ErrorLayer::default());
+    tracing::subscriber::with_default(subscriber, || {
+        let path = "/srv/my app";
+        tracing::info_span!("request", request_id = 42, method = "GET").in_scope(|| {
+            tracing::info_span!("load", path, note = ?"say \"hi\"")
+                .in_scope(|| trasy::error!("not found"))
+        })
+    })
+}
+
+#[test]
+fn spans_are_listed_innermost_first() {
+    let error = capture();
+    let spans: Vec<_> = error.spans().collect();
+
+    let names: Vec<&str> = spans.iter().map(|span| span.name()).collect();
+    assert_eq!(names, ["load", "request"]);
+    assert_eq!(spans[0].target(), module_path!());
+    assert_eq!(spans[0].file(), Some(file!()));
+}
+
+#[test]
+fn field_reads_recorded_values() {
+    let error = capture();
+    let request = error.spans().find(|span| span.name() == "request").unwrap();
+    assert_eq!(request.field("request_id"), Some("42"));
+    assert_eq!(request.field("method"), Some("GET"));
+    assert_eq!(request.field("missing"), None);
+
+    let load = error.spans().next().unwrap();
+    assert_eq!(load.field("path"), Some("/srv/my app"));
+    assert_eq!(load.field("note"), Some(r#"say "hi""#));
+    assert_eq!(
+        load.fields().map(|(key, _)| key).collect::<Vec<_>>(),
+        ["path", "note"]
+    );
+}
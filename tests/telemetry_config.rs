@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::io;
+
+use trasy::TelemetryConfig;
+
+// The configured endpoint, or the `io::Error` the builder rejected it with.
+fn build_with_endpoint(endpoint: &str) -> Result<String, io::Error> {
+    TelemetryConfig::builder()
+        .service_name("checkout")
+        .endpoint(endpoint)
+        .build()
+        .map(|config| config.endpoint().to_string())
+        .map_err(|error| {
+            let source = error.source().unwrap().downcast_ref::<io::Error>();
+            io::Error::new(source.unwrap().kind(), source.unwrap().to_string())
+        })
+}
+
+#[test]
+fn endpoint_without_scheme_is_rejected() {
+    let error = build_with_endpoint("collector:4318").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(
+        error.to_string(),
+        "endpoint `collector:4318` must start with http:// or https://"
+    );
+}
+
+#[test]
+fn endpoint_with_unsupported_scheme_is_rejected() {
+    let error = build_with_endpoint("ftp://collector:4318").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn endpoint_without_host_is_rejected() {
+    for endpoint in ["http://", "https://:4318", "http:///v1/traces"] {
+        let error = build_with_endpoint(endpoint).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("endpoint `{}` has no host", endpoint)
+        );
+    }
+}
+
+#[test]
+fn valid_endpoint_is_kept() {
+    let endpoint = build_with_endpoint("https://collector.internal:4318").unwrap();
+    assert_eq!(endpoint, "https://collector.internal:4318");
+
+    let config = TelemetryConfig::builder().build().unwrap();
+    assert_eq!(config.endpoint(), "http://localhost:4318");
+}
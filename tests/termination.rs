@@ -0,0 +1,69 @@
+use std::io;
+
+use thiserror::Error;
+use trasy::{ErrorKind, Report, TrasyError, TrasyErrorKind};
+
+#[derive(Error, Debug)]
+#[error("config file is missing")]
+struct MissingConfig;
+
+impl TrasyErrorKind for MissingConfig {
+    fn code(&self) -> &'static str {
+        "E3001"
+    }
+
+    fn category(&self) -> &'static str {
+        "config"
+    }
+
+    fn exit_code(&self) -> Option<u8> {
+        Some(78)
+    }
+
+    fn kinds() -> Vec<ErrorKind> {
+        vec![ErrorKind::of(&MissingConfig)]
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("usage: app <input>")]
+struct Usage;
+
+#[test]
+fn success_exits_with_zero() {
+    let report = Report::from(Ok::<(), trasy::Error>(()));
+    assert!(report.is_success());
+    assert_eq!(report.exit_code(), 0);
+}
+
+#[test]
+fn errors_without_a_kind_exit_with_one() {
+    let report = Report::from(TrasyError::new(io::Error::other("boom")));
+    assert_eq!(report.exit_code(), 1);
+}
+
+#[test]
+fn exit_code_comes_from_the_error_kind() {
+    let report = Report::from(TrasyError::new_with_kind(MissingConfig));
+    assert_eq!(report.exit_code(), 78);
+
+    let error = TrasyError::new_with_kind(MissingConfig).boxed();
+    assert_eq!(Report::from(error).exit_code(), 78);
+}
+
+#[test]
+fn mapper_falls_back_to_the_kind_and_with_exit_code_wins() {
+    trasy::set_exit_code_mapper(|error| error.downcast_ref::<Usage>().map(|_| 64));
+
+    assert_eq!(Report::from(TrasyError::new(Usage)).exit_code(), 64);
+    assert_eq!(
+        Report::from(TrasyError::new_with_kind(MissingConfig)).exit_code(),
+        78
+    );
+    assert_eq!(
+        Report::from(TrasyError::new_with_kind(MissingConfig))
+            .with_exit_code(3)
+            .exit_code(),
+        3
+    );
+}
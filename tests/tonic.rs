@@ -0,0 +1,132 @@
+#![cfg(feature = "tonic")]
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::ApiError;
+use prost::Message;
+use tonic::Code;
+use trasy::{set_backtrace_policy_for, BacktracePolicy, GrpcStatusOptions, ResultExt, TrasyError};
+
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ErrorInfo {
+    #[prost(string, tag = "1")]
+    reason: String,
+    #[prost(string, tag = "2")]
+    domain: String,
+    #[prost(map = "string, string", tag = "3")]
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DebugInfo {
+    #[prost(string, repeated, tag = "1")]
+    stack_entries: Vec<String>,
+    #[prost(string, tag = "2")]
+    detail: String,
+}
+
+// Decodes `grpc-status-details-bin`.
+fn rpc_status(status: &tonic::Status) -> RpcStatus {
+    RpcStatus::decode(status.details()).unwrap()
+}
+
+fn error_info(status: &RpcStatus) -> ErrorInfo {
+    assert_eq!(
+        status.details[0].type_url,
+        "type.googleapis.com/google.rpc.ErrorInfo"
+    );
+    ErrorInfo::decode(status.details[0].value.as_slice()).unwrap()
+}
+
+// The options are global, so every case that depends on them runs here.
+#[test]
+fn status_details_follow_the_options() {
+    set_backtrace_policy_for::<ApiError>(BacktracePolicy::Always);
+
+    let status = tonic::Status::from(TrasyError::new(ApiError::NotFound));
+    assert_eq!(status.code(), Code::NotFound);
+    assert_eq!(status.message(), "user not found");
+
+    let details = rpc_status(&status);
+    assert_eq!(details.code, Code::NotFound as i32);
+    assert_eq!(details.message, "user not found");
+    assert_eq!(details.details.len(), 1);
+    let info = error_info(&details);
+    assert_eq!(info.reason, "USER_NOT_FOUND");
+    assert_eq!(info.domain, "trasy");
+
+    trasy::set_grpc_status_options(
+        GrpcStatusOptions::new()
+            .domain("users.example.com")
+            .debug_info(true),
+    );
+    let status = tonic::Status::from(TrasyError::new(ApiError::Pool));
+    let details = rpc_status(&status);
+    assert_eq!(details.details.len(), 2);
+    let info = error_info(&details);
+    assert_eq!(info.reason, "UNAVAILABLE");
+    assert_eq!(info.domain, "users.example.com");
+
+    assert_eq!(
+        details.details[1].type_url,
+        "type.googleapis.com/google.rpc.DebugInfo"
+    );
+    let debug_info = DebugInfo::decode(details.details[1].value.as_slice()).unwrap();
+    assert_eq!(debug_info.detail, "connection pool exhausted");
+    assert!(debug_info
+        .stack_entries
+        .iter()
+        .any(|entry| entry.contains("status_details_follow_the_options")));
+}
+
+#[test]
+fn internal_messages_are_not_sent() {
+    let status = tonic::Status::from(TrasyError::new(ApiError::Pool));
+    assert_eq!(status.code(), Code::Unavailable);
+    assert_eq!(status.message(), Code::Unavailable.description());
+    assert!(!status.message().contains("pool"));
+
+    let error: trasy::Error = std::io::Error::other("disk on fire").into();
+    let status = tonic::Status::from(error);
+    assert_eq!(status.code(), Code::Internal);
+    assert!(!status.message().contains("disk"));
+}
+
+#[test]
+fn context_keeps_the_status_of_the_wrapped_error() {
+    let result: Result<(), ApiError> = Err(ApiError::NotFound);
+    let status = tonic::Status::from(result.context("loading the profile").unwrap_err());
+
+    assert_eq!(status.code(), Code::NotFound);
+    assert_eq!(status.message(), "user not found");
+    assert_eq!(error_info(&rpc_status(&status)).reason, "USER_NOT_FOUND");
+}
+
+#[test]
+fn the_traceparent_is_the_one_of_the_current_span() {
+    let mut status = None;
+    let span = common::export_span(|| {
+        status = Some(tonic::Status::from(TrasyError::new(ApiError::NotFound)));
+    });
+    let status = status.unwrap();
+
+    let trace_id = span.span_context.trace_id().to_string();
+    let traceparent = format!("00-{}-{}-01", trace_id, span.span_context.span_id());
+    assert_eq!(status.metadata().get("traceparent").unwrap(), &traceparent);
+    assert_eq!(
+        error_info(&rpc_status(&status)).metadata["trace_id"],
+        trace_id
+    );
+}
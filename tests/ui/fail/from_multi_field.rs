@@ -0,0 +1,14 @@
+use trasy::Trasy;
+
+#[derive(Debug, thiserror::Error, Trasy)]
+enum AppError {
+    #[error("invalid")]
+    #[trasy(code = "E1", category = "app")]
+    Invalid {
+        field: String,
+        #[trasy(from)]
+        reason: std::num::ParseIntError,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use trasy::Trasy;
+
+#[derive(Debug, thiserror::Error, Trasy)]
+enum AppError {
+    #[error("not found")]
+    #[trasy(code = "E1", category = "app", http = 1000)]
+    NotFound,
+}
+
+fn main() {}
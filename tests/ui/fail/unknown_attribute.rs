@@ -0,0 +1,10 @@
+use trasy::Trasy;
+
+#[derive(Debug, thiserror::Error, Trasy)]
+enum AppError {
+    #[error("not found")]
+    #[trasy(code = "E1", category = "app", status = 404)]
+    NotFound,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use trasy::Trasy;
+
+#[derive(Debug, thiserror::Error, Trasy)]
+enum AppError {
+    #[error("not found")]
+    #[trasy(code = "E1", category = "app", grpc = "Missing")]
+    NotFound,
+}
+
+fn main() {}
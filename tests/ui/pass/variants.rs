@@ -0,0 +1,34 @@
+use std::io;
+use std::num::ParseIntError;
+
+use trasy::{Trasy, TrasyError};
+
+#[derive(Debug, thiserror::Error, Trasy)]
+#[trasy(category = "app")]
+enum AppError {
+    #[error("unit")]
+    #[trasy(code = "E1", http = 404, grpc = "NotFound")]
+    Unit,
+
+    #[error("tuple")]
+    #[trasy(code = "E2", severity = "critical", retryable)]
+    Tuple(#[source] #[trasy(from)] ParseIntError),
+
+    #[error("struct")]
+    #[trasy(code = "E3", category = "io", exit_code = 74)]
+    Struct {
+        #[source]
+        #[trasy(from)]
+        source: io::Error,
+    },
+
+    #[error("fields")]
+    #[trasy(code = "E4", doc_url = "https://docs.example.com/E4")]
+    Fields { id: u64, name: String },
+}
+
+fn main() {
+    let _: AppError = "x".parse::<u8>().unwrap_err().into();
+    let _: AppError = io::Error::other("io").into();
+    let _: TrasyError<AppError> = AppError::Unit.into();
+}
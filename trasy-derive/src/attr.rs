@@ -0,0 +1,155 @@
+use proc_macro2::Span;
+use syn::{Attribute, Ident, LitInt, LitStr, Result};
+
+const SEVERITIES: &[&str] = &["info", "warning", "error", "critical"];
+
+// The variants of `tonic::Code`.
+const GRPC_CODES: &[&str] = &[
+    "Ok",
+    "Cancelled",
+    "Unknown",
+    "InvalidArgument",
+    "DeadlineExceeded",
+    "NotFound",
+    "AlreadyExists",
+    "PermissionDenied",
+    "ResourceExhausted",
+    "FailedPrecondition",
+    "Aborted",
+    "OutOfRange",
+    "Unimplemented",
+    "Internal",
+    "Unavailable",
+    "DataLoss",
+    "Unauthenticated",
+];
+
+// `#[trasy(...)]` on the enum.
+#[derive(Default)]
+pub struct EnumAttrs {
+    pub category: Option<LitStr>,
+}
+
+// `#[trasy(...)]` on a variant.
+#[derive(Default)]
+pub struct VariantAttrs {
+    pub code: Option<LitStr>,
+    pub category: Option<LitStr>,
+    pub doc_url: Option<LitStr>,
+    pub http: Option<LitInt>,
+    pub grpc: Option<Ident>,
+    pub severity: Option<Ident>,
+    pub retryable: bool,
+    pub exit_code: Option<LitInt>,
+}
+
+pub fn enum_attrs(attrs: &[Attribute]) -> Result<EnumAttrs> {
+    let mut result = EnumAttrs::default();
+    for attr in trasy_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("category") {
+                result.category = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `category`"))
+            }
+        })?;
+    }
+    Ok(result)
+}
+
+pub fn variant_attrs(attrs: &[Attribute]) -> Result<VariantAttrs> {
+    let mut result = VariantAttrs::default();
+    for attr in trasy_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                result.code = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("category") {
+                result.category = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("doc_url") {
+                result.doc_url = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("http") {
+                let status: LitInt = meta.value()?.parse()?;
+                if !(100..=999).contains(&status.base10_parse::<u16>()?) {
+                    return Err(syn::Error::new(
+                        status.span(),
+                        "HTTP status must be between 100 and 999",
+                    ));
+                }
+                result.http = Some(status);
+            } else if meta.path.is_ident("grpc") {
+                let code: LitStr = meta.value()?.parse()?;
+                result.grpc = Some(grpc_code(&code)?);
+            } else if meta.path.is_ident("severity") {
+                let severity: LitStr = meta.value()?.parse()?;
+                result.severity = Some(severity_variant(&severity)?);
+            } else if meta.path.is_ident("retryable") {
+                result.retryable = true;
+            } else if meta.path.is_ident("exit_code") {
+                let exit_code: LitInt = meta.value()?.parse()?;
+                if exit_code.base10_parse::<u8>().is_err() {
+                    return Err(syn::Error::new(
+                        exit_code.span(),
+                        "exit code must be between 0 and 255",
+                    ));
+                }
+                result.exit_code = Some(exit_code);
+            } else {
+                return Err(meta.error(
+                    "expected `code`, `category`, `doc_url`, `http`, `grpc`, `severity`, `retryable` or `exit_code`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(result)
+}
+
+// Whether a field has `#[trasy(from)]`.
+pub fn is_from(attrs: &[Attribute]) -> Result<bool> {
+    let mut from = false;
+    for attr in trasy_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("from") {
+                from = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `from`"))
+            }
+        })?;
+    }
+    Ok(from)
+}
+
+fn trasy_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("trasy"))
+}
+
+// Checked here, since a string that is not a valid identifier would make
+// `Ident::new` panic and an unknown variant would fail inside generated code.
+fn grpc_code(code: &LitStr) -> Result<Ident> {
+    let value = code.value();
+    if !GRPC_CODES.contains(&value.as_str()) {
+        return Err(syn::Error::new_spanned(
+            code,
+            format!(
+                "unknown gRPC code `{}`, expected a variant of `tonic::Code` such as `NotFound`",
+                value
+            ),
+        ));
+    }
+    Ok(Ident::new(&value, code.span()))
+}
+
+fn severity_variant(severity: &LitStr) -> Result<Ident> {
+    let value = severity.value();
+    if !SEVERITIES.contains(&value.as_str()) {
+        return Err(syn::Error::new_spanned(
+            severity,
+            "severity must be one of `info`, `warning`, `error` or `critical`",
+        ));
+    }
+    let mut name = value[..1].to_uppercase();
+    name.push_str(&value[1..]);
+    Ok(Ident::new(&name, Span::call_site()))
+}
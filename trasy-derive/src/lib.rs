@@ -0,0 +1,286 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Result, Variant};
+
+mod attr;
+
+use attr::VariantAttrs;
+
+// Implements `TrasyErrorKind` for an error enum, `HttpStatus` and
+// `GrpcStatus` when variants declare `http`/`grpc` statuses, and `From`
+// impls that wrap the enum in a `TrasyError` and sources in the enum.
+#[proc_macro_derive(Trasy, attributes(trasy))]
+pub fn derive_trasy(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct VariantInfo<'a> {
+    variant: &'a Variant,
+    attrs: VariantAttrs,
+    code: LitStr,
+    category: LitStr,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(Trasy)] only supports enums",
+        ));
+    };
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[derive(Trasy)] needs at least one variant",
+        ));
+    }
+
+    let enum_attrs = attr::enum_attrs(&input.attrs)?;
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let attrs = attr::variant_attrs(&variant.attrs)?;
+            let code = attrs.code.clone().ok_or_else(|| {
+                syn::Error::new_spanned(variant, "missing #[trasy(code = \"...\")]")
+            })?;
+            let category = attrs
+                .category
+                .clone()
+                .or_else(|| enum_attrs.category.clone())
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        variant,
+                        "missing #[trasy(category = \"...\")] on the variant or the enum",
+                    )
+                })?;
+            Ok(VariantInfo {
+                variant,
+                attrs,
+                code,
+                category,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let kind = expand_kind(input, &variants);
+    let http = expand_http(input, &variants);
+    let grpc = expand_grpc(input, &variants);
+    let from = expand_from(input, &variants)?;
+    Ok(quote! {
+        #kind
+        #http
+        #grpc
+        #from
+    })
+}
+
+fn expand_kind(input: &DeriveInput, variants: &[VariantInfo]) -> TokenStream2 {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let patterns: Vec<_> = variants
+        .iter()
+        .map(|info| {
+            let ident = &info.variant.ident;
+            quote!(Self::#ident { .. })
+        })
+        .collect();
+
+    let codes = variants.iter().map(|info| &info.code);
+    let categories = variants.iter().map(|info| &info.category);
+    let doc_urls: Vec<_> = variants
+        .iter()
+        .map(|info| match &info.attrs.doc_url {
+            Some(doc_url) => quote!(::core::option::Option::Some(#doc_url)),
+            None => quote!(::core::option::Option::None),
+        })
+        .collect();
+    let severities = variants.iter().map(|info| match &info.attrs.severity {
+        Some(severity) => quote!(::trasy::Severity::#severity),
+        None => quote!(::trasy::Severity::Error),
+    });
+    let retryable = variants.iter().map(|info| info.attrs.retryable);
+    let exit_codes: Vec<_> = variants
+        .iter()
+        .map(|info| match &info.attrs.exit_code {
+            Some(exit_code) => quote!(::core::option::Option::Some(#exit_code)),
+            None => quote!(::core::option::Option::None),
+        })
+        .collect();
+    let kinds =
+        variants
+            .iter()
+            .zip(&doc_urls)
+            .zip(&exit_codes)
+            .map(|((info, doc_url), exit_code)| {
+                let (code, category) = (&info.code, &info.category);
+                quote! {
+                    ::trasy::ErrorKind {
+                        code: #code,
+                        category: #category,
+                        doc_url: #doc_url,
+                        exit_code: #exit_code,
+                    }
+                }
+            });
+
+    quote! {
+        impl #impl_generics ::trasy::TrasyErrorKind for #name #ty_generics #where_clause {
+            fn code(&self) -> &'static str {
+                match self {
+                    #(#patterns => #codes,)*
+                }
+            }
+
+            fn category(&self) -> &'static str {
+                match self {
+                    #(#patterns => #categories,)*
+                }
+            }
+
+            fn doc_url(&self) -> ::core::option::Option<&'static str> {
+                match self {
+                    #(#patterns => #doc_urls,)*
+                }
+            }
+
+            fn severity(&self) -> ::trasy::Severity {
+                match self {
+                    #(#patterns => #severities,)*
+                }
+            }
+
+            fn is_retryable(&self) -> bool {
+                match self {
+                    #(#patterns => #retryable,)*
+                }
+            }
+
+            fn exit_code(&self) -> ::core::option::Option<u8> {
+                match self {
+                    #(#patterns => #exit_codes,)*
+                }
+            }
+
+            fn kinds() -> ::std::vec::Vec<::trasy::ErrorKind> {
+                ::std::vec![#(#kinds),*]
+            }
+        }
+    }
+}
+
+// Needs the `axum` feature of trasy. Variants without `http` respond with 500.
+fn expand_http(input: &DeriveInput, variants: &[VariantInfo]) -> TokenStream2 {
+    if variants.iter().all(|info| info.attrs.http.is_none()) {
+        return TokenStream2::new();
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let arms = variants.iter().map(|info| {
+        let ident = &info.variant.ident;
+        let status = match &info.attrs.http {
+            Some(status) => quote!(#status),
+            None => quote!(500),
+        };
+        quote!(Self::#ident { .. } => #status)
+    });
+
+    quote! {
+        impl #impl_generics ::trasy::HttpStatus for #name #ty_generics #where_clause {
+            fn status_code(&self) -> ::trasy::__private::axum::http::StatusCode {
+                let status: u16 = match self {
+                    #(#arms,)*
+                };
+                ::trasy::__private::axum::http::StatusCode::from_u16(status)
+                    .expect("status validated by #[derive(Trasy)]")
+            }
+        }
+    }
+}
+
+// Needs the `tonic` feature of trasy. Variants without `grpc` map to
+// `Code::Internal`.
+fn expand_grpc(input: &DeriveInput, variants: &[VariantInfo]) -> TokenStream2 {
+    if variants.iter().all(|info| info.attrs.grpc.is_none()) {
+        return TokenStream2::new();
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let arms = variants.iter().map(|info| {
+        let ident = &info.variant.ident;
+        let code = match &info.attrs.grpc {
+            Some(code) => quote!(::trasy::__private::tonic::Code::#code),
+            None => quote!(::trasy::__private::tonic::Code::Internal),
+        };
+        quote!(Self::#ident { .. } => #code)
+    });
+
+    quote! {
+        impl #impl_generics ::trasy::GrpcStatus for #name #ty_generics #where_clause {
+            fn grpc_code(&self) -> ::trasy::__private::tonic::Code {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
+}
+
+// `From<Enum> for TrasyError<Enum>` captures the span trace and records the
+// kind. The orphan rule rules out `From<Source> for TrasyError<Enum>` for
+// foreign sources, so `#[trasy(from)]` sources convert into the enum.
+fn expand_from(input: &DeriveInput, variants: &[VariantInfo]) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut sources = Vec::new();
+    for info in variants {
+        for field in info.variant.fields.iter() {
+            if !attr::is_from(&field.attrs)? {
+                continue;
+            }
+            if info.variant.fields.len() != 1 {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "#[trasy(from)] requires a variant with a single field",
+                ));
+            }
+
+            let ident = &info.variant.ident;
+            let ty = &field.ty;
+            let construct = match &info.variant.fields {
+                Fields::Named(_) => {
+                    let field_name = &field.ident;
+                    quote!(Self::#ident { #field_name: source })
+                }
+                _ => quote!(Self::#ident(source)),
+            };
+            sources.push(quote! {
+                impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+                    fn from(source: #ty) -> Self {
+                        #construct
+                    }
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#name #ty_generics>
+            for ::trasy::TrasyError<#name #ty_generics> #where_clause
+        {
+            #[track_caller]
+            fn from(error: #name #ty_generics) -> Self {
+                ::trasy::TrasyError::new_with_kind(error)
+            }
+        }
+
+        #(#sources)*
+    })
+}